@@ -1,79 +1,84 @@
-#[component]
-fn BurgerMenuView(
-    label: &'static str,
-    id: String,
-    sub_menu_list: Option<Vec<Menu>>,
-    action: Option<Action>,
-) -> Element {
-    let mut state = use_context_provider(|| MenuState {
-        show: Signal::new(false),
-    });
-    let mut show = use_context::<BurgerMenuState>().show;
-    let has_children = use_signal(|| sub_menu_list.is_some());
-    let click_handler = move |_: Event<MouseData>| {
-        if has_children() {
-            state.show.set(true);
-        } else {
-            if let Some(action) = &action {
-                action.call();
-                show.set(false)
-            }
-        }
-    };
-    rsx! {
-        div { class: "burger_root_menu",
+use super::Action;
+use dioxus::prelude::*;
+use std::rc::Rc;
 
-            div {
-                class: "center",
-                id: "{id}",
-                z_index: 10,
-                onclick: click_handler,
-                "{label}"
-            }
-            if (state.show)() && sub_menu_list.is_some() {
-                BurgerMenuWrapper { show: state.show, menu_list: sub_menu_list.unwrap() }
-            }
-        }
-    }
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum CheckKind {
+    Checkbox,
+    Radio { group: String },
 }
 
-#[derive(Clone, Copy)]
-struct BurgerSubMenuState {
-    pub show: Signal<bool>,
+/// The state of a checkable menu: a checkbox bound to a `Signal<bool>`
+/// or a radio item setting a shared signal to its own value.
+#[derive(Clone)]
+pub(crate) struct Check {
+    pub kind: CheckKind,
+    is_checked: Rc<dyn Fn() -> bool>,
+    select: Action,
 }
 
-#[component]
-fn BurgerSubMenuView(
-    label: &'static str,
-    id: String,
-    sub_menu_list: Option<Vec<Menu>>,
-    action: Option<Action>,
-) -> Element {
-    let mut state = use_context_provider(|| BurgerSubMenuState {
-        show: Signal::new(false),
-    });
-    let mut show = use_context::<BurgerMenuState>().show;
-    let has_children = use_signal(|| sub_menu_list.is_some());
-    let click_handler = move |_: Event<MouseData>| {
-        if has_children() {
-            state.show.set(true);
-        } else {
-            if let Some(action) = &action {
-                action.call();
-                show.set(false)
-            }
+impl Check {
+    pub fn checkbox(signal: Signal<bool>) -> Self {
+        Self {
+            kind: CheckKind::Checkbox,
+            is_checked: Rc::new(move || *signal.read()),
+            select: Action::new(move || {
+                let mut signal = signal;
+                signal.toggle()
+            }),
         }
-    };
+    }
 
-    rsx! {
-        div {
-            id: "BurgerSubMenuView",
-            class: "center",
-            onclick: click_handler,
-            "{label}"
+    pub fn radio<T: PartialEq + Clone + 'static>(
+        group: impl Into<String>,
+        value: T,
+        signal: Signal<T>,
+    ) -> Self {
+        let value_clone = value.clone();
+        Self {
+            kind: CheckKind::Radio {
+                group: group.into(),
+            },
+            is_checked: Rc::new(move || *signal.read() == value),
+            select: Action::new(move || {
+                let mut signal = signal;
+                signal.set(value_clone.clone())
+            }),
         }
-        if (state.show)() && sub_menu_list.is_some() {
-            BurgerMenuWrapper { show: state.show, menu_list: sub_menu_list.unwrap() }
+    }
+
+    /// Reads the bound signal, subscribing the current component
+    pub fn is_checked(&self) -> bool {
+        (self.is_checked)()
+    }
+
+    pub fn select(&self) {
+        self.select.call()
+    }
+
+    pub fn role(&self) -> &'static str {
+        match self.kind {
+            CheckKind::Checkbox => "menuitemcheckbox",
+            CheckKind::Radio { .. } => "menuitemradio",
         }
     }
+
+    pub fn group(&self) -> Option<&str> {
+        match &self.kind {
+            CheckKind::Checkbox => None,
+            CheckKind::Radio { group } => Some(group),
+        }
+    }
+}
+
+impl std::fmt::Debug for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Check({:?})", self.kind)
+    }
+}
+
+impl PartialEq for Check {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind && Rc::ptr_eq(&self.is_checked, &other.is_checked)
+    }
 }
@@ -0,0 +1,204 @@
+use dioxus::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{window, HtmlElement, Node};
+
+use super::controller::MenuController;
+use super::hover::{HoverConfig, OpenOn};
+use super::menu::{menu_list_id, permitted, with_ids, Menu, MenuPermissions, MenuState};
+use super::nav::{self, use_menu_level};
+use super::placement::{
+    flyout_style, place_at, use_resize_observer, use_window_resize_and_scroll, FlyoutPosition,
+};
+use super::pointer::{is_mouse, use_long_press};
+use super::MenuBarState;
+
+/// Opens `menu_list` at the pointer on a right click over `children`, or on a long press
+/// on touch screens. Nested menus, checks and the keyboard work like in a `MenuBar` dropdown.
+///
+/// The menu is moved to stay on screen, also when its size changes, and closes on Escape,
+/// on a click outside and when the page scrolls.
+/// `on_open_change` tells when it opens or closes.
+/// Menus without a key get ids starting with `id_prefix`. Give every context menu of a page a unique
+/// one, e.g. with the id of its table row, otherwise their ids repeat.
+///
+/// ```
+/// ContextMenu { menu_list: row_menu(row.id), id_prefix: "row_{row.id}",
+///     div { class: "row", "{row.name}" }
+/// }
+/// ```
+#[component]
+pub fn ContextMenu(
+    menu_list: Vec<Menu>,
+    on_open_change: Option<EventHandler<bool>>,
+    #[props(into, default = "context".to_string())] id_prefix: String,
+    #[props(into, default)] permissions: MenuPermissions,
+    children: Element,
+) -> Element {
+    const HEADER_CLASS: Asset = asset!("/assets/header.css");
+    let mut menu_list = with_ids(menu_list, &id_prefix);
+    if let Some(held) = permissions.get() {
+        menu_list = permitted(menu_list, &held);
+    }
+    // its own controller, so it never opens the dropdowns of a `MenuBar`
+    let controller = use_hook(MenuController::new);
+    let mut bar = use_context_provider(|| {
+        let hover = HoverConfig {
+            open_on: OpenOn::Hover,
+            open_delay: 100,
+            close_delay: 300,
+        };
+        MenuBarState::new(controller, hover)
+    });
+    use_context_provider(|| controller.children);
+    let mut state = use_context_provider(|| MenuState {
+        show: Signal::new(false),
+        anchor: Signal::new(None),
+    });
+    let is_empty = menu_list.is_empty();
+    let mut point = use_signal(|| (0.0, 0.0));
+    let mut long_press = use_long_press();
+    // the focus goes back where it was once the menu closes, it has no root to return to
+    let mut return_focus: Signal<Option<HtmlElement>> = use_signal(|| None);
+
+    // the whole menu is the opened "root" of its bar
+    let id_clone_1 = id_prefix.clone();
+    use_effect(move || {
+        let open = bar.is_open(&id_clone_1);
+        if *state.show.peek() == open {
+            return;
+        }
+        state.show.set(open);
+        // starts from the menu itself, not from the item focused last time
+        if open && !bar.focused_menu.peek().is_empty() {
+            bar.focused_menu.set(String::new());
+        }
+        if open {
+            let active = window()
+                .and_then(|w| w.document())
+                .and_then(|d| d.active_element())
+                .and_then(|a| a.dyn_into::<HtmlElement>().ok());
+            return_focus.set(active);
+        } else if let Some(element) = return_focus.take() {
+            _ = element.focus();
+        }
+        if let Some(on_open_change) = on_open_change {
+            on_open_change.call(open);
+        }
+    });
+
+    let id_clone_2 = id_prefix.clone();
+    let id_clone_3 = id_prefix.clone();
+    rsx! {
+        document::Stylesheet { href: "{HEADER_CLASS}" }
+        div {
+            class: "context_menu_target",
+            oncontextmenu: move |e: Event<MouseData>| {
+                e.prevent_default();
+                let p = e.client_coordinates();
+                point.set((p.x, p.y));
+                bar.open(&id_clone_2);
+            },
+            onpointerdown: move |e: Event<PointerData>| {
+                if is_mouse(&e) || is_empty {
+                    return;
+                }
+                let p = e.client_coordinates();
+                let id = id_clone_3.clone();
+                long_press.start(move || {
+                    point.set((p.x, p.y));
+                    bar.open(&id);
+                });
+            },
+            onpointerup: move |_| long_press.cancel(),
+            onpointercancel: move |_| long_press.cancel(),
+            {children}
+        }
+        if (state.show)() && !is_empty {
+            ContextMenuWrapper { parent: id_prefix.clone(), point: point(), menu_list }
+            div {
+                class: "dropback",
+                z_index: 10,
+                aria_hidden: "true",
+                onclick: move |_| {
+                    // the click ending the long press which opened the menu
+                    if !long_press.take_fired() {
+                        bar.close();
+                    }
+                },
+                oncontextmenu: move |e: Event<MouseData>| {
+                    e.prevent_default();
+                    bar.close();
+                },
+            }
+        }
+    }
+}
+
+#[component]
+fn ContextMenuWrapper(parent: String, point: (f64, f64), menu_list: Vec<Menu>) -> Element {
+    let mut bar = use_context::<MenuBarState>();
+    let mut position: Signal<Option<FlyoutPosition>> = use_signal(|| None);
+    let mut element: Signal<Option<web_sys::Element>> = use_signal(|| None);
+    let level = use_menu_level(menu_list.clone(), None);
+    use_window_resize_and_scroll(move |event| {
+        // scrolling a long menu itself keeps it open
+        let target = event.target().and_then(|t| t.dyn_into::<Node>().ok());
+        let inside = element
+            .peek()
+            .as_ref()
+            .is_some_and(|menu| menu.contains(target.as_ref()));
+        if !inside {
+            bar.close();
+        }
+    });
+    // e.g. when a nested menu loads its children
+    use_resize_observer(element, move || {
+        let new_position = element
+            .peek()
+            .as_ref()
+            .and_then(|menu| place_at(point, menu));
+        if *position.peek() != new_position {
+            position.set(new_position);
+        }
+    });
+    let mut focus = move |target: Option<String>| {
+        if let Some(target) = target {
+            bar.focused_menu.set(target);
+        }
+    };
+    rsx! {
+        div {
+            z_index: 11,
+            class: "context_menu",
+            id: menu_list_id(&parent),
+            role: "menu",
+            tabindex: -1,
+            style: flyout_style(position()),
+            onmounted: move |elem: Event<MountedData>| {
+                use dioxus::web::WebEventExt;
+                let menu = elem.as_web_event();
+                position.set(place_at(point, &menu));
+                element.set(Some(menu));
+                // the arrows move from here to the items
+                spawn(async move {
+                    _ = elem.data().set_focus(true).await;
+                });
+            },
+            // runs before the click of any item, nested ones included
+            onpointerdown: move |e: Event<PointerData>| bar.set_pointer(&e),
+            // the items handle their keys, these only reach the menu itself
+            onkeydown: move |e: Event<KeyboardData>| {
+                match e.key() {
+                    Key::ArrowDown | Key::Home => focus(level.items.with(|i| nav::first(i))),
+                    Key::ArrowUp | Key::End => focus(level.items.with(|i| nav::last(i))),
+                    Key::Escape | Key::Tab => bar.close(),
+                    _ => return,
+                }
+                e.prevent_default();
+            },
+            for menu in menu_list {
+                {menu.render()}
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use dioxus::prelude::*;
+
+use super::lazy::ChildrenCache;
+use super::menu::Menu;
+use super::nav;
+
+/// A handle to open, close and focus the menus of the `MenuBar` and `MenuList` rendered below
+/// the component calling `use_menu_bar`. Menus are addressed by their `Menu::key`.
+///
+/// `on_open_change` lives on the `MenuBar` and `MenuList`. From the controller, read
+/// `opened` or `is_burger_open` inside a `use_effect` to follow the open menus.
+///
+/// ```
+/// let mut menu = use_menu_bar();
+/// use_effect(move || info!("open: {:?}", menu.opened()));
+/// rsx! {
+///     button { onclick: move |_| menu.open("help"), "Help" }
+///     MenuBar { menu_list: vec![Menu::new("Help").key("help").to_root().children(help)] }
+/// }
+/// ```
+#[derive(Clone, Copy, PartialEq)]
+pub struct MenuController {
+    pub(crate) opened_menu: Signal<String>,
+    pub(crate) focused_menu: Signal<String>,
+    pub(crate) roots: Signal<Vec<Menu>>,
+    /// The nested menus currently open
+    pub(crate) expanded: Signal<Vec<String>>,
+    /// The nested menus asked to open by `open` and `focus`
+    pub(crate) expand_request: Signal<Vec<String>>,
+    pub(crate) burger: Signal<bool>,
+    pub(crate) children: ChildrenCache,
+}
+
+impl MenuController {
+    pub(crate) fn new() -> Self {
+        Self {
+            opened_menu: Signal::new("".to_string()),
+            focused_menu: Signal::new("".to_string()),
+            roots: Signal::new(vec![]),
+            expanded: Signal::new(vec![]),
+            expand_request: Signal::new(vec![]),
+            burger: Signal::new(false),
+            children: ChildrenCache::new(),
+        }
+    }
+
+    fn path_to(&self, key: &str) -> Option<Vec<String>> {
+        self.roots.with_peek(|r| nav::path_to(r, key))
+    }
+
+    /// Opens the menu `key` of the `MenuBar` together with its ancestors
+    pub fn open(&mut self, key: &str) {
+        let Some(path) = self.path_to(key) else {
+            return;
+        };
+        self.opened_menu.set(path[0].clone());
+        self.expand_request.set(path[1..].to_vec());
+    }
+
+    /// Closes the menu `key`, or everything when `key` is a root
+    pub fn close(&mut self, key: &str) {
+        let Some(mut path) = self.path_to(key) else {
+            return;
+        };
+        if path.len() == 1 {
+            self.close_all();
+        } else {
+            path.pop();
+            self.expand_request.set(path[1..].to_vec());
+        }
+    }
+
+    pub fn toggle(&mut self, key: &str) {
+        if self.is_open(key) {
+            self.close(key);
+        } else {
+            self.open(key);
+        }
+    }
+
+    /// Closes the dropdowns of the `MenuBar` and the burger `MenuList`
+    pub fn close_all(&mut self) {
+        self.opened_menu.set("".to_string());
+        self.expand_request.set(vec![]);
+        self.burger.set(false);
+    }
+
+    /// Moves the keyboard focus to the menu `key`, opening what holds it
+    pub fn focus(&mut self, key: &str) {
+        let Some(mut path) = self.path_to(key) else {
+            return;
+        };
+        path.pop();
+        if let Some((root, nested)) = path.split_first() {
+            self.opened_menu.set(root.clone());
+            self.expand_request.set(nested.to_vec());
+        }
+        self.focused_menu.set(key.to_string());
+    }
+
+    /// Whether the menu `key` shows its children, subscribing the current component
+    pub fn is_open(&self, key: &str) -> bool {
+        (self.opened_menu)() == key || self.expanded.read().iter().any(|k| k == key)
+    }
+
+    /// The opened root of the `MenuBar`, subscribing the current component
+    pub fn opened(&self) -> Option<String> {
+        let opened = (self.opened_menu)();
+        (!opened.is_empty()).then_some(opened)
+    }
+
+    pub fn open_burger(&mut self) {
+        self.burger.set(true);
+    }
+
+    pub fn close_burger(&mut self) {
+        self.burger.set(false);
+    }
+
+    /// Whether the burger `MenuList` is open, subscribing the current component
+    pub fn is_burger_open(&self) -> bool {
+        (self.burger)()
+    }
+}
+
+/// Creates a `MenuController` shared with the `MenuBar` and `MenuList` below the current component.
+pub fn use_menu_bar() -> MenuController {
+    use_context_provider(MenuController::new)
+}
+
+/// The controller of the app when `use_menu_bar` was called above, otherwise a private one.
+pub(crate) fn use_controller() -> MenuController {
+    use_hook(|| try_consume_context::<MenuController>().unwrap_or_else(MenuController::new))
+}
@@ -0,0 +1,171 @@
+use dioxus::prelude::*;
+
+use super::controller::MenuController;
+use super::hover::{HoverConfig, OpenOn};
+use super::menu::{
+    menu_list_id, permitted, with_ids, Menu, MenuPermissions, MenuState, SubMenuWrapper,
+};
+use super::nav::{self, use_menu_focus};
+use super::placement::Placement;
+use super::MenuBarState;
+use crate::icon;
+
+/// A button opening `menu_list` below it (or as set by `placement`), e.g. an "Actions ▾" button.
+///
+/// `trigger` is the content of the button, it should not be focusable itself.
+/// The menu opens on click, `Enter`, `Space` and the arrows, and closes like a `MenuBar`
+/// dropdown: on Escape, `Tab`, a click outside or once an item is chosen.
+/// Menus without a key get ids starting with `id_prefix`. Give every dropdown of a page a unique
+/// one, e.g. with the id of its table row, otherwise their ids repeat.
+///
+/// ```
+/// Dropdown {
+///     trigger: rsx! { "Actions ▾" },
+///     menu_list: actions,
+///     placement: Placement::BelowEnd,
+///     id_prefix: "actions_{row.id}",
+/// }
+/// ```
+#[component]
+pub fn Dropdown(
+    trigger: Element,
+    menu_list: Vec<Menu>,
+    #[props(default)] placement: Placement,
+    on_open_change: Option<EventHandler<bool>>,
+    #[props(into, default = "dropdown".to_string())] id_prefix: String,
+    #[props(into, default)] permissions: MenuPermissions,
+    #[props(into, default)] aria_label: Option<String>,
+) -> Element {
+    const HEADER_CLASS: Asset = asset!("/assets/header.css");
+    let mut menu_list = with_ids(menu_list, &id_prefix);
+    if let Some(held) = permissions.get() {
+        menu_list = permitted(menu_list, &held);
+    }
+    // its own controller, so it never opens the dropdowns of a `MenuBar`
+    let controller = use_hook(MenuController::new);
+    let mut bar = use_context_provider(|| {
+        let hover = HoverConfig {
+            open_on: OpenOn::Click,
+            open_delay: 100,
+            close_delay: 300,
+        };
+        MenuBarState::new(controller, hover)
+    });
+    use_context_provider(|| controller.children);
+    let mut state = use_context_provider(|| MenuState {
+        show: Signal::new(false),
+        anchor: Signal::new(None),
+    });
+    // the button is the root of its bar, Escape in the menu gives it the focus back
+    let id = id_prefix;
+    let mut element = use_menu_focus(id.clone(), bar.focused_menu);
+
+    let id_clone_1 = id.clone();
+    use_effect(move || {
+        let open = bar.is_open(&id_clone_1);
+        if *state.show.peek() == open {
+            return;
+        }
+        state.show.set(open);
+        if let Some(on_open_change) = on_open_change {
+            on_open_change.call(open);
+        }
+    });
+
+    let id_clone_2 = id.clone();
+    let click_handler = move |_: Event<MouseData>| {
+        if bar.is_open(&id_clone_2) {
+            bar.close();
+        } else {
+            bar.open(&id_clone_2);
+        }
+    };
+
+    let key_handler = {
+        let id = id.clone();
+        let menu_list = menu_list.clone();
+        move |e: Event<KeyboardData>| {
+            let mut open_and_focus = |target: Option<String>| {
+                bar.open(&id);
+                if let Some(target) = target {
+                    bar.focused_menu.set(target);
+                }
+            };
+            match e.key() {
+                Key::ArrowDown => open_and_focus(nav::first(&menu_list)),
+                Key::ArrowUp => open_and_focus(nav::last(&menu_list)),
+                ref k if nav::is_activation(k) => open_and_focus(nav::first(&menu_list)),
+                Key::Escape => bar.close(),
+                _ => return,
+            }
+            e.prevent_default();
+        }
+    };
+
+    let has_children = !menu_list.is_empty();
+    rsx! {
+        document::Stylesheet { href: "{HEADER_CLASS}" }
+        div {
+            class: "dropdown",
+            // runs before the click of any item, nested ones included
+            onpointerdown: move |e: Event<PointerData>| bar.set_pointer(&e),
+            div {
+                class: "dropdown_trigger",
+                id: "{id}",
+                role: "button",
+                tabindex: 0,
+                aria_label,
+                aria_haspopup: has_children.then_some("menu"),
+                aria_expanded: has_children.then(|| (state.show)().to_string()),
+                aria_controls: has_children.then(|| menu_list_id(&id)),
+                onmounted: move |elem: Event<MountedData>| {
+                    use dioxus::web::WebEventExt;
+                    element.set(Some(elem.data()));
+                    state.anchor.set(Some(elem.as_web_event()));
+                },
+                onclick: click_handler,
+                onkeydown: key_handler,
+                {trigger}
+            }
+            if has_children {
+                SubMenuWrapper {
+                    parent: id.clone(),
+                    show: state.show,
+                    sub_menu_list: menu_list,
+                    placement,
+                }
+            }
+        }
+    }
+}
+
+/// A button running `on_click` next to a `Dropdown` with more choices, e.g. "Save | ▾ Save as…".
+///
+/// ```
+/// SplitButton { label: rsx! { "Save" }, on_click: move |_| save(), menu_list: save_options }
+/// ```
+#[component]
+pub fn SplitButton(
+    label: Element,
+    on_click: EventHandler<MouseEvent>,
+    menu_list: Vec<Menu>,
+    #[props(default = Placement::BelowEnd)] placement: Placement,
+    on_open_change: Option<EventHandler<bool>>,
+    #[props(into, default = "split".to_string())] id_prefix: String,
+    #[props(into, default)] permissions: MenuPermissions,
+) -> Element {
+    rsx! {
+        div { class: "split_button", role: "group",
+            button { class: "split_button_main", onclick: move |e| on_click.call(e), {label} }
+            Dropdown {
+                trigger: icon!(LdChevronDown, 16, "none", "currentColor"),
+                menu_list,
+                placement,
+                on_open_change,
+                id_prefix,
+                permissions,
+                aria_label: "More options",
+            }
+        }
+    }
+}
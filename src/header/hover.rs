@@ -0,0 +1,157 @@
+use dioxus::prelude::*;
+use std::time::Duration;
+
+use super::placement::Rect;
+
+/// How the root menus of a `MenuBar` open their dropdown.
+/// Nested menus always open on hover.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum OpenOn {
+    #[default]
+    Hover,
+    /// Roots open on click, then hovering another root switches to it
+    Click,
+}
+
+/// The hover settings of a `MenuBar`, delays are in ms
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct HoverConfig {
+    pub open_on: OpenOn,
+    pub open_delay: u64,
+    pub close_delay: u64,
+}
+
+/// How often a pending hover action checks again while the pointer travels to a flyout
+const AIM_CHECK: u64 = 50;
+
+type Point = (f64, f64);
+
+/// The triangle between where the pointer left an item and the near edge of the item's flyout.
+/// While the pointer moves inside it, the user is heading to the flyout.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct SafeTriangle {
+    apex: Point,
+    top: Point,
+    bottom: Point,
+}
+
+impl SafeTriangle {
+    pub fn new(apex: Point, flyout: Rect) -> Self {
+        let edge = if flyout.x >= apex.0 {
+            flyout.x
+        } else {
+            flyout.x + flyout.width
+        };
+        Self {
+            apex,
+            top: (edge, flyout.y),
+            bottom: (edge, flyout.y + flyout.height),
+        }
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        let side =
+            |a: Point, b: Point| (point.0 - b.0) * (a.1 - b.1) - (a.0 - b.0) * (point.1 - b.1);
+        let d1 = side(self.apex, self.top);
+        let d2 = side(self.top, self.bottom);
+        let d3 = side(self.bottom, self.apex);
+        let has_negative = d1 < 0.0 || d2 < 0.0 || d3 < 0.0;
+        let has_positive = d1 > 0.0 || d2 > 0.0 || d3 > 0.0;
+        !(has_negative && has_positive)
+    }
+}
+
+/// Where the pointer is within one dropdown level and which flyout it may be heading to
+#[derive(Clone, Copy)]
+pub(crate) struct HoverIntent {
+    pub pointer: Signal<Point>,
+    /// The triangle towards the flyout of the item with this id
+    aim: Signal<Option<(String, SafeTriangle)>>,
+}
+
+impl HoverIntent {
+    pub fn new() -> Self {
+        Self {
+            pointer: Signal::new((0.0, 0.0)),
+            aim: Signal::new(None),
+        }
+    }
+
+    /// Whether the pointer is on its way to the last left flyout
+    pub fn is_aiming(&self) -> bool {
+        let pointer = *self.pointer.peek();
+        self.aim
+            .peek()
+            .as_ref()
+            .is_some_and(|(_, aim)| aim.contains(pointer))
+    }
+
+    /// Heads to the flyout of the item `owner`
+    pub fn aim(&mut self, owner: &str, aim: SafeTriangle) {
+        self.aim.set(Some((owner.to_string(), aim)));
+    }
+
+    /// Forgets the aim if it heads to the flyout of `owner`, a sibling may own it by now
+    pub fn clear_aim(&mut self, owner: &str) {
+        // the level may go away first
+        let Ok(mut aim) = self.aim.try_write() else {
+            return;
+        };
+        if aim.as_ref().is_some_and(|(id, _)| id == owner) {
+            *aim = None;
+        }
+    }
+}
+
+/// A delayed hover action, starting a new one cancels the pending one.
+#[derive(Clone, Copy)]
+pub(crate) struct HoverTimer {
+    task: Signal<Option<Task>>,
+}
+
+impl HoverTimer {
+    pub fn new() -> Self {
+        Self {
+            task: Signal::new(None),
+        }
+    }
+
+    pub fn cancel(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.cancel();
+        }
+    }
+
+    /// Runs `f` after `delay` ms. With an `intent`, it keeps waiting while
+    /// the pointer is still moving towards a flyout of the same level.
+    pub fn start(&mut self, delay: u64, intent: Option<HoverIntent>, f: impl FnOnce() + 'static) {
+        self.cancel();
+        let aiming = intent.is_some_and(|i| i.is_aiming());
+        if delay == 0 && !aiming {
+            f();
+            return;
+        }
+        let mut task = self.task;
+        let delay = if aiming { delay.max(AIM_CHECK) } else { delay };
+        self.task.set(Some(spawn(async move {
+            let mut last = intent.map(|i| *i.pointer.peek());
+            loop {
+                gloo_timers::future::sleep(Duration::from_millis(delay)).await;
+                match intent {
+                    // a pointer at rest has arrived, even inside the triangle
+                    Some(i) if i.is_aiming() && last != Some(*i.pointer.peek()) => {
+                        last = Some(*i.pointer.peek());
+                    }
+                    _ => break,
+                }
+            }
+            task.set(None);
+            f();
+        })));
+    }
+}
+
+/// A `HoverTimer` owned by the current component
+pub(crate) fn use_hover_timer() -> HoverTimer {
+    use_hook(HoverTimer::new)
+}
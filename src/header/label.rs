@@ -0,0 +1,105 @@
+use dioxus::prelude::*;
+use std::rc::Rc;
+
+/// The text of a menu: a fixed string, a signal or a closure.
+///
+/// Reactive labels are read inside the menu's own component,
+/// so a change re-renders only that menu.
+///
+/// ```
+/// let user = use_signal(|| "guest".to_string());
+/// Menu::new("Settings");
+/// Menu::new(format!("Hello {}", user()));
+/// Menu::new(move || format!("Logged in as {}", user()));
+/// ```
+#[derive(Clone)]
+pub enum MenuLabel {
+    Text(String),
+    Reactive(ReadOnlySignal<String>),
+    Dynamic(Rc<dyn Fn() -> String>),
+}
+
+impl MenuLabel {
+    /// Reads the label, subscribing the current component when reactive
+    pub fn get(&self) -> String {
+        match self {
+            MenuLabel::Text(text) => text.clone(),
+            MenuLabel::Reactive(signal) => signal(),
+            MenuLabel::Dynamic(f) => f(),
+        }
+    }
+
+    /// Only a fixed label can be empty, used to skip empty group headings
+    pub fn is_empty(&self) -> bool {
+        matches!(self, MenuLabel::Text(text) if text.is_empty())
+    }
+}
+
+impl Default for MenuLabel {
+    fn default() -> Self {
+        MenuLabel::Text(String::new())
+    }
+}
+
+impl std::fmt::Debug for MenuLabel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MenuLabel::Text(text) => write!(f, "MenuLabel({text:?})"),
+            MenuLabel::Reactive(_) => write!(f, "MenuLabel(Reactive)"),
+            MenuLabel::Dynamic(_) => write!(f, "MenuLabel(Dynamic)"),
+        }
+    }
+}
+
+impl PartialEq for MenuLabel {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (MenuLabel::Text(a), MenuLabel::Text(b)) => a == b,
+            (MenuLabel::Reactive(a), MenuLabel::Reactive(b)) => a == b,
+            (MenuLabel::Dynamic(a), MenuLabel::Dynamic(b)) => Rc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl From<&str> for MenuLabel {
+    fn from(text: &str) -> Self {
+        MenuLabel::Text(text.to_string())
+    }
+}
+
+impl From<String> for MenuLabel {
+    fn from(text: String) -> Self {
+        MenuLabel::Text(text)
+    }
+}
+
+impl From<&String> for MenuLabel {
+    fn from(text: &String) -> Self {
+        MenuLabel::Text(text.clone())
+    }
+}
+
+impl From<Signal<String>> for MenuLabel {
+    fn from(signal: Signal<String>) -> Self {
+        MenuLabel::Reactive(signal.into())
+    }
+}
+
+impl From<ReadOnlySignal<String>> for MenuLabel {
+    fn from(signal: ReadOnlySignal<String>) -> Self {
+        MenuLabel::Reactive(signal)
+    }
+}
+
+impl From<Memo<String>> for MenuLabel {
+    fn from(memo: Memo<String>) -> Self {
+        MenuLabel::Reactive(memo.into())
+    }
+}
+
+impl<F: Fn() -> String + 'static> From<F> for MenuLabel {
+    fn from(f: F) -> Self {
+        MenuLabel::Dynamic(Rc::new(f))
+    }
+}
@@ -0,0 +1,204 @@
+use dioxus::prelude::*;
+use std::{
+    any::TypeId, collections::HashMap, fmt, future::Future, pin::Pin, rc::Rc, time::Duration,
+};
+
+use super::menu::{permitted, with_ids, Menu};
+use super::Action;
+use crate::icon;
+
+/// What the loader of `Menu::children_async` may return: the menus, or a `Result`
+/// whose error is shown in the dropdown together with a retry item.
+pub trait IntoChildren {
+    fn into_children(self) -> Result<Vec<Menu>, String>;
+}
+
+impl IntoChildren for Vec<Menu> {
+    fn into_children(self) -> Result<Vec<Menu>, String> {
+        Ok(self)
+    }
+}
+
+impl<E: fmt::Display> IntoChildren for Result<Vec<Menu>, E> {
+    fn into_children(self) -> Result<Vec<Menu>, String> {
+        self.map_err(|e| e.to_string())
+    }
+}
+
+type LoadFuture = Pin<Box<dyn Future<Output = Result<Vec<Menu>, String>>>>;
+
+/// Loads the children of a menu when it is first expanded
+#[derive(Clone)]
+pub(crate) struct ChildrenLoader {
+    load: Rc<dyn Fn() -> LoadFuture>,
+    /// The type of the closure given to `children_async`, the same on every render
+    kind: TypeId,
+    /// The `Menu::key` of the menu, when it has one
+    pub key: Option<String>,
+    /// How long loaded children are kept, `None` for as long as the menus live
+    pub ttl: Option<Duration>,
+    /// The permissions of the nav the menu is in, filtering the loaded children
+    pub held: Option<Vec<String>>,
+}
+
+impl ChildrenLoader {
+    pub fn new<F, Fut, T>(f: F) -> Self
+    where
+        F: Fn() -> Fut + 'static,
+        Fut: Future<Output = T> + 'static,
+        T: IntoChildren,
+    {
+        Self {
+            load: Rc::new(move || {
+                let future = f();
+                Box::pin(async move { future.await.into_children() })
+            }),
+            kind: TypeId::of::<F>(),
+            key: None,
+            ttl: None,
+            held: None,
+        }
+    }
+
+    /// Where the loaded children are cached, the same for the `MenuBar` and the burger
+    /// `MenuList` rendering the menu, unlike the ids which come from their `id_prefix`.
+    pub fn cache_key(&self) -> String {
+        self.key
+            .clone()
+            .unwrap_or_else(|| format!("{:?}", self.kind))
+    }
+}
+
+impl fmt::Debug for ChildrenLoader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ChildrenLoader({}, {:?}, {:?})",
+            self.cache_key(),
+            self.ttl,
+            self.held
+        )
+    }
+}
+
+impl PartialEq for ChildrenLoader {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.load, &other.load)
+            && self.key == other.key
+            && self.ttl == other.ttl
+            && self.held == other.held
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Children {
+    Loading,
+    /// `at` is when they were loaded, in ms since the page opened
+    Loaded {
+        menus: Vec<Menu>,
+        at: f64,
+    },
+    Failed(String),
+}
+
+/// The children loaded so far, by `ChildrenLoader::cache_key`. It outlives closed dropdowns
+/// and lives in the `MenuController`: with `use_menu_bar` the `MenuBar` and the burger
+/// `MenuList` share it, otherwise each has its own, lost when it unmounts,
+/// e.g. when `Header` switches between them.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) struct ChildrenCache(pub Signal<HashMap<String, Children>>);
+
+impl ChildrenCache {
+    pub fn new() -> Self {
+        Self(Signal::new(HashMap::new()))
+    }
+
+    fn needs_load(&self, loader: &ChildrenLoader) -> bool {
+        let ttl = loader.ttl;
+        match self.0.peek().get(&loader.cache_key()) {
+            None | Some(Children::Failed(_)) => true,
+            Some(Children::Loading) => false,
+            Some(Children::Loaded { at, .. }) => {
+                ttl.is_some_and(|ttl| now() - at >= ttl.as_millis() as f64)
+            }
+        }
+    }
+
+    /// Runs `loader`. The task is not tied to the dropdown,
+    /// so closing it before the children arrive still fills the cache.
+    fn load(mut self, loader: ChildrenLoader) {
+        let key = loader.cache_key();
+        self.0.write().insert(key.clone(), Children::Loading);
+        spawn_forever(async move {
+            let children = match (loader.load)().await {
+                Ok(menus) => Children::Loaded { menus, at: now() },
+                Err(error) => Children::Failed(error),
+            };
+            if let Ok(mut cache) = self.0.try_write() {
+                cache.insert(key, children);
+            }
+        });
+    }
+}
+
+fn now() -> f64 {
+    web_sys::window()
+        .and_then(|w| w.performance())
+        .map_or(0.0, |p| p.now())
+}
+
+/// The children to render for the menu `id`: `sub_menu_list` as is, or for a `loader`
+/// what it loaded, a loading row, or the error and a retry item.
+/// Loading starts each time `open` turns true and nothing fresh is cached.
+pub(crate) fn use_children(
+    id: &str,
+    sub_menu_list: Option<Vec<Menu>>,
+    loader: Option<ChildrenLoader>,
+    open: Signal<bool>,
+) -> Option<Vec<Menu>> {
+    let cache =
+        use_hook(|| try_consume_context::<ChildrenCache>().unwrap_or_else(ChildrenCache::new));
+    let id = id.to_string();
+    use_effect(use_reactive((&loader,), move |(loader,)| {
+        if !open() {
+            return;
+        }
+        if let Some(loader) = loader {
+            if cache.needs_load(&loader) {
+                cache.load(loader);
+            }
+        }
+    }));
+
+    let Some(loader) = loader else {
+        return sub_menu_list;
+    };
+    let children = cache.0.read().get(&loader.cache_key()).cloned();
+    let rows = match children {
+        // ids under the id of this menu, the cached ones are shared between navs
+        Some(Children::Loaded { menus, .. }) => {
+            let menus = with_ids(menus, &id);
+            match &loader.held {
+                Some(held) => permitted(menus, held),
+                None => menus,
+            }
+        }
+        None | Some(Children::Loading) => vec![Menu::new("Loading…")
+            .key(format!("{id}_loading"))
+            .icon(icon!(LdLoader, 16, "none", "currentColor"))
+            .disabled(true)],
+        Some(Children::Failed(error)) => {
+            let (error_key, retry_key) = (format!("{id}_error"), format!("{id}_retry"));
+            let retry = Action::new(move || cache.load(loader.clone()));
+            vec![
+                Menu::new(error).key(error_key).disabled(true),
+                Menu::new("Retry")
+                    .key(retry_key)
+                    .icon(icon!(LdRotateCw, 16, "none", "currentColor"))
+                    .keep_open()
+                    .with_action(retry),
+            ]
+        }
+    };
+    Some(rows)
+}
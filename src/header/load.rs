@@ -0,0 +1,370 @@
+use dioxus::prelude::*;
+use serde::Deserialize;
+use std::{collections::HashMap, fmt, rc::Rc};
+
+use super::menu::Menu;
+use super::route::MenuRoute;
+use super::Action;
+
+/// What a `MenuDef` turns into
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MenuDefKind {
+    #[default]
+    Item,
+    Separator,
+    Heading,
+    Group,
+}
+
+/// A menu as written in a JSON, TOML or RON file, turned into a `Menu` by `load_menus`.
+///
+/// Actions, icons and routes are referenced by name and resolved through a `MenuRegistry`.
+///
+/// ```json
+/// [
+///     { "label": "File", "children": [
+///         { "label": "Save", "action": "save", "icon": "save", "shortcut": "Ctrl+S" },
+///         { "kind": "separator" },
+///         { "label": "Users", "route": "/admin/users", "roles": ["admin"] }
+///     ]}
+/// ]
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MenuDef {
+    pub kind: MenuDefKind,
+    pub label: String,
+    pub key: Option<String>,
+    pub action: Option<String>,
+    pub icon: Option<String>,
+    pub route: Option<String>,
+    pub shortcut: Option<String>,
+    pub badge: Option<String>,
+    pub disabled: bool,
+    /// The menu is shown only when the `permissions` hold all these roles, see `Menu::requires`
+    pub roles: Vec<String>,
+    pub children: Vec<MenuDef>,
+}
+
+/// A whole menu file, for formats like TOML which need a table at the top:
+///
+/// ```toml
+/// [[menu]]
+/// label = "File"
+///
+/// [[menu.children]]
+/// label = "Save"
+/// action = "save"
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MenuTree {
+    pub menu: Vec<MenuDef>,
+}
+
+impl MenuTree {
+    pub fn load(&self, registry: &MenuRegistry) -> Result<Vec<Menu>, MenuLoadError> {
+        load_menus(&self.menu, registry)
+    }
+}
+
+type RouteParser = Rc<dyn Fn(&str) -> Option<MenuRoute>>;
+
+/// The actions, icons and routes the names of a `MenuDef` point to.
+///
+/// ```
+/// let registry = MenuRegistry::new()
+///     .action("save", move || save())
+///     .icon("save", || icon!(LdSave, 18));
+/// ```
+#[derive(Clone, Default)]
+pub struct MenuRegistry {
+    actions: HashMap<String, Action>,
+    icons: HashMap<String, Rc<dyn Fn() -> Element>>,
+    routes: Option<RouteParser>,
+}
+
+impl MenuRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn action<F: Fn() + 'static>(mut self, name: impl Into<String>, f: F) -> Self {
+        self.actions.insert(name.into(), Action::new(f));
+        self
+    }
+
+    pub fn icon<F: Fn() -> Element + 'static>(mut self, name: impl Into<String>, f: F) -> Self {
+        self.icons.insert(name.into(), Rc::new(f));
+        self
+    }
+
+    /// Parses the `route` of the menus into routes of the app
+    #[cfg(feature = "router")]
+    pub fn routes<R: dioxus::router::prelude::Routable>(mut self) -> Self {
+        self.routes = Some(Rc::new(|path| path.parse::<R>().ok().map(MenuRoute::new)));
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MenuLoadErrorKind {
+    UnknownAction(String),
+    UnknownIcon(String),
+    UnknownRoute(String),
+    /// A menu has a route but the registry has no `routes`, or the `router` feature is off
+    NoRoutes,
+    /// A separator or heading has children
+    ChildrenNotAllowed(MenuDefKind),
+}
+
+/// A name of a `MenuDef` missing from the `MenuRegistry`, or a `MenuDef` which makes no sense.
+/// `path` holds the labels from the root down to the menu, `#2` for an unlabelled third item.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MenuLoadError {
+    pub path: Vec<String>,
+    pub kind: MenuLoadErrorKind,
+}
+
+impl fmt::Display for MenuLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            MenuLoadErrorKind::UnknownAction(name) => write!(f, "unknown action `{name}`")?,
+            MenuLoadErrorKind::UnknownIcon(name) => write!(f, "unknown icon `{name}`")?,
+            MenuLoadErrorKind::UnknownRoute(path) => write!(f, "unknown route `{path}`")?,
+            MenuLoadErrorKind::NoRoutes => {
+                write!(f, "a route is set but the registry has no routes")?
+            }
+            MenuLoadErrorKind::ChildrenNotAllowed(kind) => {
+                let kind = format!("{kind:?}").to_lowercase();
+                write!(f, "a {kind} can't have children")?
+            }
+        }
+        write!(f, " at `{}`", self.path.join(" > "))
+    }
+}
+
+impl std::error::Error for MenuLoadError {}
+
+/// Turns `defs` into the menus of a `MenuBar` or `MenuList`, the outer ones become roots.
+pub fn load_menus(defs: &[MenuDef], registry: &MenuRegistry) -> Result<Vec<Menu>, MenuLoadError> {
+    let menus = load_list(defs, registry, &[])?;
+    Ok(menus.into_iter().map(Menu::to_root).collect())
+}
+
+fn load_list(
+    defs: &[MenuDef],
+    registry: &MenuRegistry,
+    parent: &[String],
+) -> Result<Vec<Menu>, MenuLoadError> {
+    defs.iter()
+        .enumerate()
+        .map(|(i, def)| {
+            let mut path = parent.to_vec();
+            path.push(if def.label.is_empty() {
+                format!("#{i}")
+            } else {
+                def.label.clone()
+            });
+            load_one(def, registry, path)
+        })
+        .collect()
+}
+
+fn load_one(
+    def: &MenuDef,
+    registry: &MenuRegistry,
+    path: Vec<String>,
+) -> Result<Menu, MenuLoadError> {
+    let error = |kind| MenuLoadError {
+        path: path.clone(),
+        kind,
+    };
+    let leaf = matches!(def.kind, MenuDefKind::Separator | MenuDefKind::Heading);
+    if leaf && !def.children.is_empty() {
+        return Err(error(MenuLoadErrorKind::ChildrenNotAllowed(def.kind)));
+    }
+    let children = load_list(&def.children, registry, &path)?;
+
+    let mut menu = match def.kind {
+        MenuDefKind::Item => Menu::new(def.label.as_str()),
+        MenuDefKind::Separator => Menu::separator(),
+        MenuDefKind::Heading => Menu::heading(def.label.as_str()),
+        MenuDefKind::Group => Menu::group(def.label.as_str(), vec![]),
+    };
+    if !children.is_empty() || def.kind == MenuDefKind::Group {
+        menu = menu.children(children);
+    }
+    if let Some(key) = &def.key {
+        menu = menu.key(key);
+    }
+    if let Some(name) = &def.action {
+        let action = registry.actions.get(name);
+        let action = action.ok_or_else(|| error(MenuLoadErrorKind::UnknownAction(name.clone())))?;
+        menu = menu.with_action(action.clone());
+    }
+    if let Some(name) = &def.icon {
+        let icon = registry.icons.get(name);
+        let icon = icon.ok_or_else(|| error(MenuLoadErrorKind::UnknownIcon(name.clone())))?;
+        menu = menu.icon(icon());
+    }
+    if let Some(target) = &def.route {
+        let parse = registry
+            .routes
+            .as_ref()
+            .ok_or_else(|| error(MenuLoadErrorKind::NoRoutes))?;
+        let route = parse(target);
+        menu = menu.with_route(
+            route.ok_or_else(|| error(MenuLoadErrorKind::UnknownRoute(target.clone())))?,
+        );
+    }
+    if let Some(shortcut) = &def.shortcut {
+        menu = menu.shortcut(shortcut);
+    }
+    if let Some(badge) = &def.badge {
+        menu = menu.badge(badge);
+    }
+    if def.disabled {
+        menu = menu.disabled(true);
+    }
+    for role in &def.roles {
+        menu = menu.requires(role);
+    }
+    Ok(menu)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn def(label: &str) -> MenuDef {
+        MenuDef {
+            label: label.into(),
+            ..MenuDef::default()
+        }
+    }
+
+    fn with_children(label: &str, children: Vec<MenuDef>) -> MenuDef {
+        MenuDef {
+            children,
+            ..def(label)
+        }
+    }
+
+    fn load_error(defs: Vec<MenuDef>, registry: &MenuRegistry) -> MenuLoadError {
+        load_menus(&defs, registry).unwrap_err()
+    }
+
+    #[test]
+    fn unknown_action_gives_the_path_of_the_menu() {
+        let save = MenuDef {
+            action: Some("save".into()),
+            ..def("Save")
+        };
+        let error = load_error(
+            vec![with_children("File", vec![save])],
+            &MenuRegistry::new(),
+        );
+        assert_eq!(error.kind, MenuLoadErrorKind::UnknownAction("save".into()));
+        assert_eq!(error.path, vec!["File", "Save"]);
+        assert_eq!(error.to_string(), "unknown action `save` at `File > Save`");
+    }
+
+    #[test]
+    fn unknown_icon_names_unlabelled_menus_by_position() {
+        let icon = MenuDef {
+            icon: Some("star".into()),
+            ..def("")
+        };
+        let defs = vec![def("Edit"), with_children("View", vec![def("Zoom"), icon])];
+        let error = load_error(defs, &MenuRegistry::new());
+        assert_eq!(error.kind, MenuLoadErrorKind::UnknownIcon("star".into()));
+        assert_eq!(error.to_string(), "unknown icon `star` at `View > #1`");
+    }
+
+    #[test]
+    fn route_without_routes_in_the_registry() {
+        let users = MenuDef {
+            route: Some("/users".into()),
+            ..def("Users")
+        };
+        let error = load_error(vec![users], &MenuRegistry::new());
+        assert_eq!(error.kind, MenuLoadErrorKind::NoRoutes);
+        assert_eq!(error.path, vec!["Users"]);
+    }
+
+    #[test]
+    fn unknown_route() {
+        let registry = MenuRegistry {
+            routes: Some(Rc::new(|_| None)),
+            ..MenuRegistry::new()
+        };
+        let users = MenuDef {
+            route: Some("/nope".into()),
+            ..def("Users")
+        };
+        let error = load_error(vec![with_children("Admin", vec![users])], &registry);
+        assert_eq!(error.kind, MenuLoadErrorKind::UnknownRoute("/nope".into()));
+        assert_eq!(
+            error.to_string(),
+            "unknown route `/nope` at `Admin > Users`"
+        );
+    }
+
+    #[test]
+    fn separators_and_headings_cannot_have_children() {
+        let separator = MenuDef {
+            kind: MenuDefKind::Separator,
+            ..with_children("", vec![def("Lost")])
+        };
+        let error = load_error(
+            vec![with_children("File", vec![def("Open"), separator])],
+            &MenuRegistry::new(),
+        );
+        assert_eq!(
+            error.kind,
+            MenuLoadErrorKind::ChildrenNotAllowed(MenuDefKind::Separator)
+        );
+        assert_eq!(
+            error.to_string(),
+            "a separator can't have children at `File > #1`"
+        );
+
+        let heading = MenuDef {
+            kind: MenuDefKind::Heading,
+            ..with_children("Recent", vec![def("a.txt")])
+        };
+        let error = load_error(vec![heading], &MenuRegistry::new());
+        assert_eq!(
+            error.kind,
+            MenuLoadErrorKind::ChildrenNotAllowed(MenuDefKind::Heading)
+        );
+        assert_eq!(error.path, vec!["Recent"]);
+    }
+
+    #[test]
+    fn roles_become_required_permissions() {
+        let users = MenuDef {
+            roles: vec!["admin".into(), "users.read".into()],
+            ..def("Users")
+        };
+        let defs = vec![
+            with_children("Admin", vec![users]),
+            MenuDef {
+                kind: MenuDefKind::Separator,
+                ..def("")
+            },
+        ];
+        let menus = load_menus(&defs, &MenuRegistry::new()).unwrap();
+        let expected = vec![
+            Menu::new("Admin")
+                .children(vec![Menu::new("Users")
+                    .requires("admin")
+                    .requires("users.read")])
+                .to_root(),
+            Menu::separator().to_root(),
+        ];
+        assert_eq!(menus, expected);
+    }
+}
@@ -0,0 +1,108 @@
+use dioxus::prelude::*;
+
+use super::hover::OpenOn;
+use super::menu::{menu_list_id, Menu, MenuState};
+use super::nav::use_menu_level;
+use super::overlay::tab_leaves;
+use super::placement::{panel_style, use_full_width};
+use super::pointer::is_mouse;
+use super::MenuBarState;
+
+/// What the full-width panel of a root shows, see `Menu::panel` and `Menu::columns`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct MegaPanel {
+    /// Shown after the children
+    pub content: Option<Element>,
+    /// Each child is a column, otherwise the children share one column
+    pub columns: bool,
+}
+
+/// The panel of a mega menu root, spanning the width of the `MenuBar` under it.
+/// It opens and closes like the dropdown of `SubMenuWrapper`, the children are
+/// reached with the arrows and the custom content with `Tab`.
+#[component]
+pub(crate) fn MegaMenuWrapper(
+    parent: String,
+    show: Signal<bool>,
+    sub_menu_list: Option<Vec<Menu>>,
+    panel: MegaPanel,
+) -> Element {
+    let mut bar = use_context::<MenuBarState>();
+    let anchor = use_context::<MenuState>().anchor;
+    let position = use_full_width(anchor, bar.element, show);
+    use_menu_level(sub_menu_list.clone().unwrap_or_default(), None);
+    let mut content: Signal<Option<web_sys::Element>> = use_signal(|| None);
+    rsx! {
+        if show() {
+            div {
+                z_index: 11,
+                class: "mega_menu",
+                id: menu_list_id(&parent),
+                role: "region",
+                aria_labelledby: "{parent}",
+                style: panel_style(position()),
+                onpointerenter: move |e: Event<PointerData>| {
+                    if is_mouse(&e) && bar.open_on() == OpenOn::Hover {
+                        bar.timer.cancel();
+                    }
+                },
+                if let Some(sub_menu_list) = sub_menu_list {
+                    div {
+                        class: "mega_menu_columns",
+                        role: "menu",
+                        aria_labelledby: "{parent}",
+                        if panel.columns {
+                            for column in sub_menu_list {
+                                div { class: "mega_menu_column", role: "none", {column.render()} }
+                            }
+                        } else {
+                            div { class: "mega_menu_column", role: "none",
+                                for sub_menu in sub_menu_list {
+                                    {sub_menu.render()}
+                                }
+                            }
+                        }
+                    }
+                }
+                if let Some(children) = panel.content {
+                    div {
+                        class: "mega_menu_content",
+                        onmounted: move |elem: Event<MountedData>| {
+                            use dioxus::web::WebEventExt;
+                            content.set(Some(elem.as_web_event()));
+                        },
+                        onkeydown: move |e: Event<KeyboardData>| {
+                            match e.key() {
+                                Key::Escape => {
+                                    e.prevent_default();
+                                    bar.close_and_focus_root();
+                                }
+                                // tabbing past the panel closes it
+                                Key::Tab if !e.modifiers().shift() => {
+                                    let leaves = content.peek().as_ref().is_some_and(|c| tab_leaves(c, false));
+                                    if leaves {
+                                        bar.close();
+                                    }
+                                }
+                                _ => {}
+                            }
+                        },
+                        {children}
+                    }
+                }
+            }
+            div {
+                class: "dropback",
+                z_index: 9,
+                aria_hidden: "true",
+                onclick: move |_| bar.close(),
+                // the pointer left the root and its panel
+                onpointerenter: move |e: Event<PointerData>| {
+                    if is_mouse(&e) && bar.open_on() == OpenOn::Hover {
+                        bar.hover_close();
+                    }
+                },
+            }
+        }
+    }
+}
@@ -1,38 +1,399 @@
-use super::{Action, BurgerMenuState, BurgerMenuWrapper};
-use dioxus::{logger::tracing::info, prelude::*};
+use super::check::{Check, CheckKind};
+use super::hover::{use_hover_timer, OpenOn, SafeTriangle};
+use super::label::MenuLabel;
+use super::lazy::{use_children, ChildrenLoader, IntoChildren};
+use super::mega::{MegaMenuWrapper, MegaPanel};
+use super::nav::{self, use_menu_focus, use_menu_level, MenuLevel};
+use super::overlay::after_overlay_entry;
+use super::palette::Command;
+use super::placement::{flyout_style, use_flyout, Placement};
+use super::pointer::{is_mouse, use_long_press};
+use super::route::{opens_elsewhere, route_link, MenuRoute};
+use super::side_nav::SideNavView;
+use super::{Action, BurgerLevel, BurgerMenuState, BurgerMenuWrapper, MenuBarState};
+use crate::icon;
+use dioxus::{
+    logger::tracing::{info, warn},
+    prelude::*,
+};
 
-// TODO:
-// add menu separator
+/// What a `Menu` entry is: only `Item`s are clickable and focusable.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) enum MenuKind {
+    #[default]
+    Item,
+    Separator,
+    Heading,
+    /// A block of items under an optional heading, the items live in `sub_menu_list`
+    Group,
+}
+
+/// Where a menu is rendered, each place has its own views
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum MenuMode {
+    Bar,
+    Burger,
+    Side,
+}
+
+/// What is shown around the label of a menu
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct MenuDecor {
+    pub icon: Option<Element>,
+    pub shortcut: Option<String>,
+    pub badge: Option<String>,
+}
+
+/// A menu state which is either fixed or follows a signal.
+///
+/// Built from a `bool`, `Signal<bool>`, `ReadOnlySignal<bool>` or `Memo<bool>`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MenuFlag {
+    Static(bool),
+    Reactive(ReadOnlySignal<bool>),
+}
+
+impl MenuFlag {
+    /// Reads the flag, subscribing the current component when reactive
+    pub fn get(&self) -> bool {
+        match self {
+            MenuFlag::Static(value) => *value,
+            MenuFlag::Reactive(signal) => signal(),
+        }
+    }
+}
+
+impl Default for MenuFlag {
+    fn default() -> Self {
+        MenuFlag::Static(false)
+    }
+}
+
+impl From<bool> for MenuFlag {
+    fn from(value: bool) -> Self {
+        MenuFlag::Static(value)
+    }
+}
+
+impl From<Signal<bool>> for MenuFlag {
+    fn from(signal: Signal<bool>) -> Self {
+        MenuFlag::Reactive(signal.into())
+    }
+}
+
+impl From<ReadOnlySignal<bool>> for MenuFlag {
+    fn from(signal: ReadOnlySignal<bool>) -> Self {
+        MenuFlag::Reactive(signal)
+    }
+}
+
+impl From<Memo<bool>> for MenuFlag {
+    fn from(memo: Memo<bool>) -> Self {
+        MenuFlag::Reactive(memo.into())
+    }
+}
+
+/// The permissions the current user holds, fixed or following a signal.
+///
+/// Built from a `Vec<String>`, `Vec<&str>`, `Signal<Vec<String>>`, `ReadOnlySignal<Vec<String>>`
+/// or `Memo<Vec<String>>`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum MenuPermissions {
+    /// Shows every menu, whatever it requires
+    #[default]
+    All,
+    Static(Vec<String>),
+    Reactive(ReadOnlySignal<Vec<String>>),
+}
+
+impl MenuPermissions {
+    /// Reads the permissions, subscribing the current component when reactive.
+    /// `None` means no filtering.
+    pub fn get(&self) -> Option<Vec<String>> {
+        match self {
+            MenuPermissions::All => None,
+            MenuPermissions::Static(held) => Some(held.clone()),
+            MenuPermissions::Reactive(signal) => Some(signal()),
+        }
+    }
+}
+
+impl From<Vec<String>> for MenuPermissions {
+    fn from(held: Vec<String>) -> Self {
+        MenuPermissions::Static(held)
+    }
+}
+
+impl From<Vec<&str>> for MenuPermissions {
+    fn from(held: Vec<&str>) -> Self {
+        MenuPermissions::Static(held.into_iter().map(str::to_string).collect())
+    }
+}
+
+impl From<Signal<Vec<String>>> for MenuPermissions {
+    fn from(signal: Signal<Vec<String>>) -> Self {
+        MenuPermissions::Reactive(signal.into())
+    }
+}
+
+impl From<ReadOnlySignal<Vec<String>>> for MenuPermissions {
+    fn from(signal: ReadOnlySignal<Vec<String>>) -> Self {
+        MenuPermissions::Reactive(signal)
+    }
+}
+
+impl From<Memo<Vec<String>>> for MenuPermissions {
+    fn from(memo: Memo<Vec<String>>) -> Self {
+        MenuPermissions::Reactive(memo.into())
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct Menu {
     id: String,
-    label: &'static str,
+    label: MenuLabel,
     action: Option<Action>,
     sub_menu_list: Option<Vec<Menu>>,
     is_root: bool,
+    kind: MenuKind,
+    decor: MenuDecor,
+    disabled: MenuFlag,
+    /// `None` means always visible
+    visible: Option<MenuFlag>,
+    check: Option<Check>,
+    keep_open: bool,
+    route: Option<MenuRoute>,
+    /// The permissions needed to see the menu
+    requires: Vec<String>,
+    loader: Option<ChildrenLoader>,
+    /// Shown instead of the dropdown of a root, see `panel` and `columns`
+    panel: Option<MegaPanel>,
 }
 
 impl Menu {
-    pub fn new(label: &'static str) -> Self {
-        let id = sam_util::gen_id!(5, "menu_");
+    /// A menu whose id comes from its `key`, or from its position in the tree by default,
+    /// e.g. `menu_1_0` for the first child of the second root.
+    pub fn new(label: impl Into<MenuLabel>) -> Self {
+        Self {
+            label: label.into(),
+            ..Menu::default()
+        }
+    }
+
+    /// A horizontal line between items (vertical when used as a root)
+    pub fn separator() -> Self {
         Self {
-            id,
-            label,
+            kind: MenuKind::Separator,
             ..Menu::default()
         }
     }
 
+    /// A non clickable title for the items following it
+    pub fn heading(label: impl Into<MenuLabel>) -> Self {
+        Self {
+            kind: MenuKind::Heading,
+            ..Menu::new(label)
+        }
+    }
+
+    /// A section of items grouped under `label`, pass an empty label for no heading.
+    pub fn group(label: impl Into<MenuLabel>, menu_list: Vec<Menu>) -> Self {
+        Self {
+            kind: MenuKind::Group,
+            sub_menu_list: Some(menu_list),
+            ..Menu::new(label)
+        }
+    }
+
     pub fn action<F: Fn() + 'static>(mut self, f: F) -> Self {
         self.action = Some(Action::new(f));
         self
     }
 
+    /// An icon shown before the label, e.g. `.icon(icon!(LdSave, 18))`
+    pub fn icon(mut self, icon: Element) -> Self {
+        self.decor.icon = Some(icon);
+        self
+    }
+
+    /// A keyboard shortcut hint shown after the label, e.g. `"Ctrl+S"`.
+    /// It is only a hint, binding the keys is left to the app.
+    pub fn shortcut(mut self, shortcut: impl Into<String>) -> Self {
+        self.decor.shortcut = Some(shortcut.into());
+        self
+    }
+
+    /// A small counter or text next to the label, e.g. `3` or `"3 new"`
+    pub fn badge(mut self, badge: impl ToString) -> Self {
+        self.decor.badge = Some(badge.to_string());
+        self
+    }
+
+    /// Greys out the menu: its action is not called and the keyboard focus skips it.
+    ///
+    /// ```
+    /// let locked = use_signal(|| true);
+    /// Menu::new("Delete").disabled(locked);
+    /// ```
+    pub fn disabled(mut self, disabled: impl Into<MenuFlag>) -> Self {
+        self.disabled = disabled.into();
+        self
+    }
+
+    /// Shows the menu only while `visible` is true
+    pub fn visible(mut self, visible: impl Into<MenuFlag>) -> Self {
+        self.visible = Some(visible.into());
+        self
+    }
+
+    /// Turns the menu into a checkbox toggling `checked` when clicked.
+    ///
+    /// ```
+    /// let show_grid = use_signal(|| false);
+    /// Menu::new("Show grid").checkbox(show_grid).keep_open();
+    /// ```
+    pub fn checkbox(mut self, checked: Signal<bool>) -> Self {
+        self.check = Some(Check::checkbox(checked));
+        self
+    }
+
+    /// Turns the menu into a radio item of `group` which sets `selected` to `value` when clicked.
+    /// The items of one group should share the same `selected` signal.
+    ///
+    /// ```
+    /// let sort = use_signal(|| Sort::Name);
+    /// Menu::group("Sort by", vec![
+    ///     Menu::new("Name").radio("sort", Sort::Name, sort),
+    ///     Menu::new("Date").radio("sort", Sort::Date, sort),
+    /// ]);
+    /// ```
+    pub fn radio<T: PartialEq + Clone + 'static>(
+        mut self,
+        group: impl Into<String>,
+        value: T,
+        selected: Signal<T>,
+    ) -> Self {
+        self.check = Some(Check::radio(group, value, selected));
+        self
+    }
+
+    /// Keeps the dropdown open after the menu is clicked, handy for checkboxes.
+    pub fn keep_open(mut self) -> Self {
+        self.keep_open = true;
+        self
+    }
+
+    /// Links the menu to a route of the app, it is rendered as a real link
+    /// and highlighted together with its ancestors while the route is active.
+    ///
+    /// ```
+    /// Menu::new("Blog").to(Route::Blog {})
+    /// ```
+    #[cfg(feature = "router")]
+    pub fn to<R: dioxus::router::prelude::Routable>(self, route: R) -> Self {
+        self.with_route(MenuRoute::new(route))
+    }
+
+    /// Shows the menu only when the `permissions` given to `MenuBar` or `MenuList`
+    /// hold `permission`, chain it to require several.
+    ///
+    /// ```
+    /// Menu::new("Users").requires("admin").requires("users.read")
+    /// ```
+    pub fn requires(mut self, permission: impl Into<String>) -> Self {
+        self.requires.push(permission.into());
+        self
+    }
+
+    pub(crate) fn with_action(mut self, action: Action) -> Self {
+        self.action = Some(action);
+        self
+    }
+
+    #[cfg_attr(not(any(feature = "router", feature = "serde")), allow(dead_code))]
+    pub(crate) fn with_route(mut self, route: MenuRoute) -> Self {
+        self.route = Some(route);
+        self
+    }
+
     pub fn children(mut self, sub_menu_list: Vec<Menu>) -> Self {
         self.sub_menu_list = Some(sub_menu_list);
         self
     }
 
+    /// Children loaded by `f` when the menu is first expanded. A loading row is shown meanwhile,
+    /// and when `f` returns an `Err` its message is shown with a retry item.
+    /// The children are kept while the nav lives, see `cache_for` to refresh them.
+    /// They are cached by `key`, or by `f` for menus without a key: give menus built
+    /// from the same closure, e.g. in a loop, their own `key`.
+    ///
+    /// ```
+    /// Menu::new("Recent").children_async(|| async {
+    ///     let files = fetch_recent().await?;
+    ///     Ok::<_, FetchError>(files.into_iter().map(|f| Menu::new(f.name)).collect())
+    /// })
+    /// ```
+    pub fn children_async<F, Fut, T>(mut self, f: F) -> Self
+    where
+        F: Fn() -> Fut + 'static,
+        Fut: std::future::Future<Output = T> + 'static,
+        T: IntoChildren,
+    {
+        let mut loader = ChildrenLoader::new(f);
+        if !self.id.is_empty() {
+            loader.key = Some(self.id.clone());
+        }
+        self.loader = Some(loader);
+        self
+    }
+
+    /// Opens a full-width panel under the `MenuBar` showing `content` instead of a dropdown,
+    /// e.g. images and promo blocks. With `columns`, `content` follows the columns.
+    /// The burger menu shows `content` after the children. Only roots have a panel,
+    /// nested menus and `SideNav` leave it out with a warning.
+    ///
+    /// ```
+    /// Menu::new("Products").to_root().panel(rsx! { ProductsPromo {} })
+    /// ```
+    pub fn panel(mut self, content: Element) -> Self {
+        self.panel.get_or_insert_with(MegaPanel::default).content = Some(content);
+        self
+    }
+
+    /// Children laid out side by side in the full-width panel of `panel`, each of them a column,
+    /// usually a `Menu::group`. Elsewhere they are plain children.
+    ///
+    /// ```
+    /// Menu::new("Products").to_root().columns(vec![
+    ///     Menu::group("Software", software),
+    ///     Menu::group("Hardware", hardware),
+    /// ])
+    /// ```
+    pub fn columns(mut self, columns: Vec<Menu>) -> Self {
+        self.sub_menu_list = Some(columns);
+        self.panel.get_or_insert_with(MegaPanel::default).columns = true;
+        self
+    }
+
+    /// Loads the children of `children_async` again when the menu is expanded
+    /// more than `ttl` after they were loaded.
+    pub fn cache_for(mut self, ttl: std::time::Duration) -> Self {
+        if let Some(loader) = &mut self.loader {
+            loader.ttl = Some(ttl);
+        }
+        self
+    }
+
+    /// A stable key, used as the element id and to address the menu through `MenuController`.
+    /// Keys must be unique within a page, the children of a keyed menu get ids like `file_0`.
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.id = key.into();
+        if let Some(loader) = &mut self.loader {
+            loader.key = Some(self.id.clone());
+        }
+        self
+    }
+
     pub fn to_root(mut self) -> Self {
         self.is_root = true;
         self
@@ -42,57 +403,486 @@ impl Menu {
         self.is_root
     }
 
-    pub fn render(self) -> Element {
+    pub(crate) fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub(crate) fn label(&self) -> String {
+        self.label.get()
+    }
+
+    pub(crate) fn is_visible(&self) -> bool {
+        self.visible.is_none_or(|v| v.get())
+    }
+
+    /// Whether the keyboard focus can land on this menu
+    pub(crate) fn is_focusable(&self) -> bool {
+        self.kind == MenuKind::Item && self.is_visible() && !self.disabled.get()
+    }
+
+    /// Whether the app is on the route of this menu or one of its children
+    pub(crate) fn is_route_active(&self) -> bool {
+        self.route.as_ref().is_some_and(|r| r.is_active())
+            || self
+                .sub_menu_list
+                .iter()
+                .flatten()
+                .any(Menu::is_route_active)
+    }
+
+    pub(crate) fn sub_menus(&self) -> Option<&[Menu]> {
+        self.sub_menu_list.as_deref()
+    }
+
+    pub(crate) fn is_group(&self) -> bool {
+        self.kind == MenuKind::Group
+    }
+
+    /// The items of a group, which are navigated as siblings of the group itself
+    pub(crate) fn group_items(&self) -> Option<&[Menu]> {
+        match self.kind {
+            MenuKind::Group if self.is_visible() => self.sub_menu_list.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn render(mut self) -> Element {
+        match self.visible.take() {
+            Some(visible) => rsx! {
+                MenuVisibility { visible, {self.render_bar()} }
+            },
+            None => self.render_bar(),
+        }
+    }
+
+    pub fn render_mob(mut self) -> Element {
+        match self.visible.take() {
+            Some(visible) => rsx! {
+                MenuVisibility { visible, {self.render_burger()} }
+            },
+            None => self.render_burger(),
+        }
+    }
+
+    /// Renders the menu in a `SideNav`
+    pub(crate) fn render_side(mut self) -> Element {
+        match self.visible.take() {
+            Some(visible) => rsx! {
+                MenuVisibility { visible, {self.render_side_item()} }
+            },
+            None => self.render_side_item(),
+        }
+    }
+
+    fn render_in(self, mode: MenuMode) -> Element {
+        match mode {
+            MenuMode::Bar => self.render(),
+            MenuMode::Burger => self.render_mob(),
+            MenuMode::Side => self.render_side(),
+        }
+    }
+
+    fn has_panel_content(&self) -> bool {
+        self.panel.as_ref().is_some_and(|p| p.content.is_some())
+    }
+
+    fn render_bar(self) -> Element {
+        match self.kind {
+            MenuKind::Separator => return rsx! { MenuSeparator { vertical: self.is_root } },
+            MenuKind::Heading => return rsx! { MenuHeading { id: self.id, label: self.label } },
+            MenuKind::Group => {
+                return rsx! {
+                    MenuGroup {
+                        id: self.id,
+                        label: self.label,
+                        menu_list: self.sub_menu_list.unwrap_or_default(),
+                        mode: MenuMode::Bar,
+                    }
+                }
+            }
+            MenuKind::Item => {}
+        }
         rsx! {
             MenuView {
                 label: self.label,
                 id: self.id,
+                decor: self.decor,
+                disabled: self.disabled,
+                check: self.check,
+                keep_open: self.keep_open,
+                route: self.route,
                 action: self.action,
                 sub_menu_list: self.sub_menu_list,
+                loader: self.loader,
+                panel: self.panel,
                 is_root: self.is_root,
             }
         }
     }
 
-    pub fn render_mob(self) -> Element {
+    fn render_burger(self) -> Element {
+        match self.kind {
+            MenuKind::Separator => return rsx! { MenuSeparator { vertical: false } },
+            MenuKind::Heading => return rsx! { MenuHeading { id: self.id, label: self.label } },
+            MenuKind::Group => {
+                return rsx! {
+                    MenuGroup {
+                        id: self.id,
+                        label: self.label,
+                        menu_list: self.sub_menu_list.unwrap_or_default(),
+                        mode: MenuMode::Burger,
+                    }
+                }
+            }
+            MenuKind::Item => {}
+        }
         rsx! {
             BurgerMenuView {
                 label: self.label,
                 id: self.id,
+                decor: self.decor,
+                disabled: self.disabled,
+                check: self.check,
+                keep_open: self.keep_open,
+                route: self.route,
                 action: self.action,
                 sub_menu_list: self.sub_menu_list,
+                loader: self.loader,
+                content: self.panel.and_then(|p| p.content),
+                is_root: self.is_root,
             }
         }
     }
+
+    fn render_side_item(self) -> Element {
+        let has_panel = self.has_panel_content();
+        match self.kind {
+            MenuKind::Separator => return rsx! { MenuSeparator { vertical: false } },
+            MenuKind::Heading => return rsx! { MenuHeading { id: self.id, label: self.label } },
+            MenuKind::Group => {
+                return rsx! {
+                    MenuGroup {
+                        id: self.id,
+                        label: self.label,
+                        menu_list: self.sub_menu_list.unwrap_or_default(),
+                        mode: MenuMode::Side,
+                    }
+                }
+            }
+            MenuKind::Item => {}
+        }
+        rsx! {
+            SideNavView {
+                label: self.label,
+                id: self.id,
+                decor: self.decor,
+                disabled: self.disabled,
+                check: self.check,
+                route: self.route,
+                action: self.action,
+                sub_menu_list: self.sub_menu_list,
+                loader: self.loader,
+                has_panel,
+            }
+        }
+    }
+}
+
+/// Warns on the first render of the menu `id` that its panel is left out, `why` saying why
+pub(crate) fn use_panel_warning(id: &str, left_out: bool, why: &str) {
+    use_hook(|| {
+        if left_out {
+            warn!("panel of `{id}` not shown, {why}");
+        }
+    });
+}
+
+/// Whether the app is on `route` (`Some(true)` for the page itself) or on one of the
+/// routes under `sub_menu_list`. Used for the `active` class and `aria-current`.
+pub(crate) fn route_state(
+    route: &Option<MenuRoute>,
+    sub_menu_list: &Option<Vec<Menu>>,
+) -> (bool, bool) {
+    let is_current = route.as_ref().is_some_and(|r| r.is_active());
+    let is_active = is_current || sub_menu_list.iter().flatten().any(Menu::is_route_active);
+    (is_active, is_current)
+}
+
+/// Runs what a click on a menu does: toggling its check then calling its action
+pub(crate) fn activate(action: &Option<Action>, check: &Option<Check>) {
+    if let Some(check) = check {
+        check.select();
+    }
+    if let Some(action) = action {
+        action.call();
+    }
+}
+
+/// Keeps the menus whose required permissions are all `held`, then drops the submenus
+/// left empty and the separators and headings left with nothing around them.
+pub(crate) fn permitted(menu_list: Vec<Menu>, held: &[String]) -> Vec<Menu> {
+    let menus = menu_list
+        .into_iter()
+        .filter(|menu| menu.requires.iter().all(|p| held.contains(p)))
+        .filter_map(|mut menu| {
+            // async children are filtered once loaded
+            if let Some(loader) = &mut menu.loader {
+                loader.held = Some(held.to_vec());
+            }
+            let Some(children) = menu.sub_menu_list.take() else {
+                return Some(menu);
+            };
+            let was_empty = children.is_empty();
+            let children = permitted(children, held);
+            if children.is_empty() && !was_empty {
+                // a submenu with nothing left only stays when clicking it does something
+                let acts = menu.action.is_some() || menu.check.is_some() || menu.route.is_some();
+                return (acts && menu.kind == MenuKind::Item).then_some(menu);
+            }
+            menu.sub_menu_list = Some(children);
+            Some(menu)
+        });
+
+    let mut list: Vec<Menu> = vec![];
+    for menu in menus {
+        if matches!(menu.kind, MenuKind::Separator | MenuKind::Heading) {
+            // a heading with nothing under it
+            if list.last().is_some_and(|m| m.kind == MenuKind::Heading) {
+                list.pop();
+            }
+            let orphan = list.last().is_none_or(|m| m.kind == MenuKind::Separator);
+            if menu.kind == MenuKind::Separator && orphan {
+                continue;
+            }
+        }
+        list.push(menu);
+    }
+    while list
+        .last()
+        .is_some_and(|m| matches!(m.kind, MenuKind::Separator | MenuKind::Heading))
+    {
+        list.pop();
+    }
+    list
+}
+
+/// Gives the menus without a key an id from their position under `prefix`,
+/// so the ids are the same on every render, on the server and on the client.
+pub(crate) fn with_ids(menu_list: Vec<Menu>, prefix: &str) -> Vec<Menu> {
+    menu_list
+        .into_iter()
+        .enumerate()
+        .map(|(i, mut menu)| {
+            if menu.id.is_empty() {
+                menu.id = format!("{prefix}_{i}");
+            }
+            if let Some(children) = menu.sub_menu_list.take() {
+                menu.sub_menu_list = Some(with_ids(children, &menu.id));
+            }
+            menu
+        })
+        .collect()
+}
+
+/// The items of `menu_list` and their children which do something when chosen, in tree order,
+/// with the labels leading to them. Hidden menus are left out with their children and the
+/// children of a disabled menu are disabled too. Children from `children_async` are not loaded.
+pub(crate) fn commands(menu_list: &[Menu], path: &[String], disabled: bool) -> Vec<Command> {
+    let mut list = vec![];
+    for menu in menu_list.iter().filter(|m| m.is_visible()) {
+        match menu.kind {
+            MenuKind::Separator | MenuKind::Heading => continue,
+            // a group only sorts its items, it is not part of their path
+            MenuKind::Group => {
+                list.extend(commands(
+                    menu.sub_menus().unwrap_or_default(),
+                    path,
+                    disabled,
+                ));
+                continue;
+            }
+            MenuKind::Item => {}
+        }
+        let mut menu_path = path.to_vec();
+        menu_path.push(menu.label());
+        let disabled = disabled || menu.disabled.get();
+        if menu.action.is_some() || menu.check.is_some() || menu.route.is_some() {
+            list.push(Command {
+                path: menu_path.clone(),
+                icon: menu.decor.icon.clone(),
+                shortcut: menu.decor.shortcut.clone(),
+                disabled,
+                check: menu.check.clone(),
+                route: menu.route.clone(),
+                action: menu.action.clone(),
+            });
+        }
+        if let Some(children) = &menu.sub_menu_list {
+            list.extend(commands(children, &menu_path, disabled));
+        }
+    }
+    list
+}
+
+/// The id of the `role="menu"` element listing the children of the menu `id`
+pub(crate) fn menu_list_id(id: &str) -> String {
+    format!("{id}_list")
 }
 
 #[derive(Clone, Copy)]
-struct MenuState {
+pub(crate) struct MenuState {
     pub show: Signal<bool>,
+    /// The root element, the dropdown is placed against it
+    pub anchor: Signal<Option<web_sys::Element>>,
+}
+
+/// Renders `children` only while `visible` is true, so only this menu re-renders when it changes.
+#[component]
+fn MenuVisibility(visible: MenuFlag, children: Element) -> Element {
+    if visible.get() {
+        children
+    } else {
+        rsx! {}
+    }
+}
+
+#[component]
+fn MenuSeparator(vertical: bool) -> Element {
+    rsx! {
+        div {
+            class: if vertical { "menu_separator vertical" } else { "menu_separator" },
+            role: "separator",
+            aria_orientation: if vertical { "vertical" } else { "horizontal" },
+        }
+    }
+}
+
+#[component]
+fn MenuHeading(id: String, label: MenuLabel) -> Element {
+    rsx! {
+        div { class: "menu_heading", id: "{id}", role: "presentation", {label.get()} }
+    }
+}
+
+/// Renders the items of a group with the views of `mode`.
+#[component]
+fn MenuGroup(id: String, label: MenuLabel, menu_list: Vec<Menu>, mode: MenuMode) -> Element {
+    let heading_id = format!("{id}_heading");
+    let has_heading = !label.is_empty();
+    rsx! {
+        div {
+            class: "menu_group",
+            role: "group",
+            aria_labelledby: has_heading.then(|| heading_id.clone()),
+            if has_heading {
+                MenuHeading { id: heading_id.clone(), label }
+            }
+            for menu in menu_list {
+                {menu.render_in(mode)}
+            }
+        }
+    }
+}
+
+/// The inside of a menu item: check indicator, icon, label, badge, shortcut hint
+/// and a chevron when the menu has children (pointing down for roots).
+#[component]
+pub(crate) fn MenuContent(
+    label: MenuLabel,
+    decor: MenuDecor,
+    check: Option<Check>,
+    has_children: bool,
+    is_root: bool,
+    /// Called by a click on the chevron instead of the menu's own click
+    on_expand: Option<EventHandler>,
+) -> Element {
+    rsx! {
+        if let Some(check) = check {
+            span { class: "menu_check", aria_hidden: "true",
+                if check.is_checked() {
+                    if check.kind == CheckKind::Checkbox {
+                        {icon!(LdCheck, 16, "none", "currentColor")}
+                    } else {
+                        {icon!(LdDot, 16, "currentColor", "currentColor")}
+                    }
+                }
+            }
+        }
+        if let Some(icon) = decor.icon {
+            span { class: "menu_icon", aria_hidden: "true", {icon} }
+        }
+        span { class: "menu_label", {label.get()} }
+        if let Some(badge) = decor.badge {
+            span { class: "menu_badge", "{badge}" }
+        }
+        if let Some(shortcut) = decor.shortcut {
+            kbd { class: "menu_shortcut", aria_hidden: "true", "{shortcut}" }
+        }
+        if has_children {
+            span {
+                class: "menu_chevron",
+                aria_hidden: "true",
+                onclick: move |e: Event<MouseData>| {
+                    if let Some(on_expand) = on_expand {
+                        e.stop_propagation();
+                        e.prevent_default();
+                        on_expand.call(());
+                    }
+                },
+                if is_root {
+                    {icon!(LdChevronDown, 16, "none", "currentColor")}
+                } else {
+                    {icon!(LdChevronRight, 16, "none", "currentColor")}
+                }
+            }
+        }
+    }
 }
 
 #[component]
 fn MenuView(
-    label: &'static str,
+    label: MenuLabel,
     id: String,
+    decor: MenuDecor,
+    disabled: MenuFlag,
+    check: Option<Check>,
+    keep_open: bool,
+    route: Option<MenuRoute>,
     sub_menu_list: Option<Vec<Menu>>,
+    loader: Option<ChildrenLoader>,
     action: Option<Action>,
+    panel: Option<MegaPanel>,
     is_root: bool,
 ) -> Element {
+    let has_content = panel.as_ref().is_some_and(|p| p.content.is_some());
+    use_panel_warning(&id, !is_root && has_content, "only roots have one");
     rsx! {
         if is_root {
             RootMenuView {
                 label,
                 id,
+                decor,
+                disabled,
+                check,
+                keep_open,
+                route,
                 action,
                 sub_menu_list,
+                loader,
+                panel,
             }
         } else {
             SubMenuView {
                 label,
                 id,
+                decor,
+                disabled,
+                check,
+                keep_open,
+                route,
                 action,
                 sub_menu_list,
+                loader,
             }
         }
     }
@@ -100,68 +890,289 @@ fn MenuView(
 
 #[component]
 fn RootMenuView(
-    label: &'static str,
+    label: MenuLabel,
     id: String,
+    decor: MenuDecor,
+    disabled: MenuFlag,
+    check: Option<Check>,
+    keep_open: bool,
+    route: Option<MenuRoute>,
     sub_menu_list: Option<Vec<Menu>>,
+    loader: Option<ChildrenLoader>,
     action: Option<Action>,
+    panel: Option<MegaPanel>,
 ) -> Element {
     let mut state = use_context_provider(|| MenuState {
         show: Signal::new(false),
+        anchor: Signal::new(None),
     });
-    let mut opened_menu: Signal<String> = use_context::<super::MenuBarState>().opened_menu;
+    let mut bar = use_context::<MenuBarState>();
+    let sub_menu_list = use_children(&id, sub_menu_list, loader, state.show);
+    let mut element = use_menu_focus(id.clone(), bar.focused_menu);
+    let has_children = sub_menu_list.is_some() || panel.is_some();
+    // the custom content of a mega menu is reached with `Tab`
+    let has_content = panel.as_ref().is_some_and(|p| p.content.is_some());
 
     let id_clone_1 = id.clone();
     let id_clone_2 = id.clone();
+    let id_clone_3 = id.clone();
+    let id_clone_5 = id.clone();
+    let id_clone_6 = id.clone();
+    let id_clone_7 = id.clone();
 
-    let handler = move |_: Event<MouseData>| {
-        state.show.toggle();
-        if (state.show)() {
-            opened_menu.set(id_clone_1.clone())
+    let handler = move |e: Event<PointerData>| {
+        // taps open on click
+        if !is_mouse(&e) {
+            return;
+        }
+        let can_open = has_children && !disabled.get();
+        match bar.open_on() {
+            OpenOn::Hover if can_open => bar.hover_open(&id_clone_1),
+            OpenOn::Hover => bar.hover_close(),
+            // once a dropdown is open, hovering another root switches to it
+            OpenOn::Click => {
+                if bar.opened_menu.peek().is_empty() || bar.is_open(&id_clone_1) {
+                    return;
+                }
+                if can_open {
+                    bar.open(&id_clone_1);
+                } else {
+                    bar.close();
+                }
+            }
         }
     };
 
     let click_handler = {
-        let mut handler = handler.clone();
+        let action = action.clone();
+        let check = check.clone();
+        let route = route.clone();
+        // a root only opening its dropdown
+        let opens_only = has_children && action.is_none() && check.is_none() && route.is_none();
         move |e: Event<MouseData>| {
-            handler(e);
-            if let Some(action) = &action {
-                action.call();
+            if disabled.get() || opens_elsewhere(&route, &e) {
+                return;
+            }
+            let touch = bar.is_touch();
+            // the first tap opens the dropdown, the second one activates the root
+            if opens_only || (touch && has_children && !bar.is_open(&id_clone_5)) {
+                e.prevent_default();
+                let toggles = touch || bar.open_on() == OpenOn::Click;
+                if toggles && bar.is_open(&id_clone_5) {
+                    bar.close();
+                } else {
+                    bar.open(&id_clone_5);
+                }
+                return;
+            }
+            activate(&action, &check);
+            if let Some(route) = &route {
+                route.push();
+            }
+            if !keep_open {
+                bar.close();
             }
-            state.show.set(false);
         }
     };
 
-    use_effect(move || {
-        opened_menu.with(|m| {
-            if m != &id_clone_2 {
-                state.show.set(false);
+    let key_handler = {
+        let sub_menu_list = sub_menu_list.clone().unwrap_or_default();
+        let check = check.clone();
+        let route = route.clone();
+        move |e: Event<KeyboardData>| {
+            let key = e.key();
+            let has_children = has_children && !disabled.get();
+            let is_open = !bar.opened_menu.peek().is_empty();
+            let content_open = has_content && bar.is_open(&id_clone_3);
+            let mut open_and_focus = |target: Option<String>| {
+                bar.open(&id_clone_3);
+                if let Some(target) = target {
+                    bar.focused_menu.set(target);
+                }
+            };
+            match key {
+                Key::ArrowRight => bar.move_root(1, is_open),
+                Key::ArrowLeft => bar.move_root(-1, is_open),
+                Key::ArrowDown if has_children => open_and_focus(nav::first(&sub_menu_list)),
+                Key::ArrowUp if has_children => open_and_focus(nav::last(&sub_menu_list)),
+                Key::Home => {
+                    if let Some(first) = bar.roots.with(|r| nav::first(r)) {
+                        bar.focused_menu.set(first);
+                    }
+                }
+                Key::End => {
+                    if let Some(last) = bar.roots.with(|r| nav::last(r)) {
+                        bar.focused_menu.set(last);
+                    }
+                }
+                Key::Escape => bar.close_and_focus_root(),
+                Key::Tab if content_open && !e.modifiers().shift() => {
+                    return;
+                }
+                Key::Tab => {
+                    bar.close();
+                    return;
+                }
+                ref k if nav::is_activation(k) => {
+                    if has_children {
+                        open_and_focus(nav::first(&sub_menu_list));
+                    } else if !disabled.get() {
+                        activate(&action, &check);
+                        if let Some(route) = &route {
+                            route.push();
+                        }
+                        if !keep_open {
+                            bar.close();
+                        }
+                    }
+                }
+                ref k => {
+                    let Some(c) = nav::typed_char(k) else { return };
+                    if let Some(target) = bar.roots.with(|r| nav::find_by_char(r, &id_clone_3, c)) {
+                        bar.focused_menu.set(target);
+                    }
+                }
             }
-        });
+            e.prevent_default();
+        }
+    };
+
+    use_effect(move || {
+        let open = bar.is_open(&id_clone_2);
+        if *state.show.peek() != open {
+            state.show.set(open);
+        }
     });
 
+    // Only one root is reachable by `Tab`, the arrows move between the others
+    let tab_index = if bar.tab_stop().as_deref() == Some(id.as_str()) {
+        0
+    } else {
+        -1
+    };
+
+    let id_clone_4 = id.clone();
+    let is_disabled = disabled.get();
+    let popup = if sub_menu_list.is_some() {
+        "menu"
+    } else {
+        "true"
+    };
+    let (is_active, is_current) = route_state(&route, &sub_menu_list);
+
     rsx! {
-        div { class: "menu_wrapper",
+        div { class: "menu_wrapper", role: "none",
 
             div {
                 class: "menu center",
+                class: if is_disabled { "disabled" },
+                class: if is_active { "active" },
+                aria_current: is_current.then_some("page"),
                 id: "{id}",
                 z_index: 10,
+                role: check.as_ref().map_or("menuitem", |c| c.role()),
+                aria_checked: check.as_ref().map(|c| c.is_checked().to_string()),
+                "data-radio-group": check.as_ref().and_then(|c| c.group()).map(str::to_string),
+                aria_disabled: is_disabled.then_some("true"),
+                aria_haspopup: has_children.then_some(popup),
+                aria_keyshortcuts: decor.shortcut.clone(),
+                aria_expanded: has_children.then(|| (state.show)().to_string()),
+                aria_controls: has_children.then(|| menu_list_id(&id)),
+                tabindex: tab_index,
+                onmounted: move |elem: Event<MountedData>| {
+                    use dioxus::web::WebEventExt;
+                    element.set(Some(elem.data()));
+                    state.anchor.set(Some(elem.as_web_event()));
+                },
+                onfocus: move |_| {
+                    if *bar.focused_menu.peek() != id_clone_4 {
+                        bar.focused_menu.set(id_clone_4.clone());
+                    }
+                },
                 onclick: click_handler,
-                onmouseenter: handler,
-                "{label}"
+                onpointerenter: handler,
+                onpointerleave: move |e: Event<PointerData>| {
+                    // cancels a pending open
+                    if is_mouse(&e) && !bar.is_open(&id_clone_6) {
+                        bar.timer.cancel();
+                    }
+                },
+                onkeydown: key_handler,
+                {
+                    route_link(
+                        &route,
+                        disabled.get(),
+                        rsx! {
+                            MenuContent {
+                                label,
+                                decor: decor.clone(),
+                                check: check.clone(),
+                                has_children,
+                                is_root: true,
+                                on_expand: move |_| {
+                                    if disabled.get() {
+                                        return;
+                                    }
+                                    if bar.is_open(&id_clone_7) {
+                                        bar.close();
+                                    } else {
+                                        bar.open(&id_clone_7);
+                                    }
+                                },
+                            }
+                        },
+                    )
+                }
+            }
+            if let Some(panel) = panel {
+                MegaMenuWrapper {
+                    parent: id.clone(),
+                    show: state.show,
+                    sub_menu_list,
+                    panel,
+                }
+            } else {
+                SubMenuWrapper {
+                    parent: id.clone(),
+                    show: state.show,
+                    sub_menu_list,
+                    placement: Placement::Below,
+                }
             }
-            SubMenuWrapper { show: state.show, sub_menu_list }
         }
     }
 }
 
+/// The dropdown of a root menu or a `Dropdown`, with the dropback closing it
 #[component]
-fn SubMenuWrapper(show: Signal<bool>, sub_menu_list: Option<Vec<Menu>>) -> Element {
-    // let mut sub_menu_wrapper: Signal<Option<web_sys::Element>> = use_signal(|| None);
-    // let mut width: Signal<f64> = use_signal(|| 0.0);
+pub(crate) fn SubMenuWrapper(
+    parent: String,
+    show: Signal<bool>,
+    sub_menu_list: Option<Vec<Menu>>,
+    placement: Placement,
+) -> Element {
+    let mut bar = use_context::<MenuBarState>();
+    let anchor = use_context::<MenuState>().anchor;
+    let (mut flyout, position) = use_flyout(anchor, show, placement);
+    use_menu_level(sub_menu_list.clone().unwrap_or_default(), None);
     rsx! {
         if show() && sub_menu_list.is_some() {
-            div { z_index: 11, class: "sub_menu_wrapper",
+            div {
+                z_index: 11,
+                class: "sub_menu_wrapper",
+                id: menu_list_id(&parent),
+                role: "menu",
+                aria_labelledby: "{parent}",
+                style: flyout_style(position()),
+                onmounted: move |elem: Event<MountedData>| {
+                    use dioxus::web::WebEventExt;
+                    flyout.set(Some(elem.as_web_event()));
+                },
+                onpointerenter: move |e: Event<PointerData>| {
+                    if is_mouse(&e) && bar.open_on() == OpenOn::Hover {
+                        bar.timer.cancel();
+                    }
+                },
                 for sub_menu in sub_menu_list.unwrap() {
                     {sub_menu.render()}
                 }
@@ -169,7 +1180,14 @@ fn SubMenuWrapper(show: Signal<bool>, sub_menu_list: Option<Vec<Menu>>) -> Eleme
             div {
                 class: "dropback",
                 z_index: 9,
-                onclick: move |_| show.set(false),
+                aria_hidden: "true",
+                onclick: move |_| bar.close(),
+                // the pointer left the root and its dropdown
+                onpointerenter: move |e: Event<PointerData>| {
+                    if is_mouse(&e) && bar.open_on() == OpenOn::Hover {
+                        bar.hover_close();
+                    }
+                },
             }
         }
     }
@@ -177,62 +1195,319 @@ fn SubMenuWrapper(show: Signal<bool>, sub_menu_list: Option<Vec<Menu>>) -> Eleme
 
 #[derive(Clone, Copy)]
 struct SubMenuState {
-    pub width: Signal<f64>,
+    /// The item element, its nested menu is placed against it
+    pub anchor: Signal<Option<web_sys::Element>>,
+    /// The nested menu element while it is shown
+    pub flyout: Signal<Option<web_sys::Element>>,
 }
 
 #[component]
 fn SubMenuView(
-    label: &'static str,
+    label: MenuLabel,
     id: String,
+    decor: MenuDecor,
+    disabled: MenuFlag,
+    check: Option<Check>,
+    keep_open: bool,
+    route: Option<MenuRoute>,
     sub_menu_list: Option<Vec<Menu>>,
+    loader: Option<ChildrenLoader>,
     action: Option<Action>,
 ) -> Element {
+    // The level this menu lives in, read before `SubSubMenuWrapper` provides its own
+    let level = use_context::<MenuLevel>();
     let mut state = use_context_provider(|| SubMenuState {
-        width: Signal::new(0.0),
+        anchor: Signal::new(None),
+        flyout: Signal::new(None),
     });
-    let mut show = use_context::<MenuState>().show;
+    let mut bar = use_context::<MenuBarState>();
     let mut show_sub_menu = use_signal(|| false);
+    let sub_menu_list = use_children(&id, sub_menu_list, loader, show_sub_menu);
+    let mut timer = use_hover_timer();
+    let mut long_press = use_long_press();
+    let mut intent = level.intent;
+    let mut element = use_menu_focus(id.clone(), bar.focused_menu);
+    let has_children = sub_menu_list.is_some();
 
-    let mut sub_menu: Signal<Option<web_sys::Element>> = use_signal(|| None);
+    // opened through the `MenuController`
+    let id_clone_1 = id.clone();
+    use_effect(move || {
+        let requested = bar.expand_request.read().contains(&id_clone_1);
+        if *show_sub_menu.peek() != requested {
+            show_sub_menu.set(requested);
+        }
+    });
+    // reported to the `MenuController`
+    let id_clone_2 = id.clone();
+    use_effect(move || {
+        let show = show_sub_menu();
+        let listed = bar.expanded.peek().contains(&id_clone_2);
+        if show && !listed {
+            bar.expanded.write().push(id_clone_2.clone());
+        } else if !show && listed {
+            bar.expanded.write().retain(|k| k != &id_clone_2);
+        }
+    });
+    // the pointer can no longer be heading to a closed nested menu
+    let id_clone_3 = id.clone();
+    use_effect(move || {
+        if !show_sub_menu() {
+            intent.clear_aim(&id_clone_3);
+        }
+    });
+    let id_clone_4 = id.clone();
+    use_drop(move || {
+        // the controller may go away with the bar
+        if let Ok(mut expanded) = bar.expanded.try_write() {
+            expanded.retain(|k| k != &id_clone_4);
+        }
+        intent.clear_aim(&id_clone_4);
+    });
+
+    let click_handler = {
+        let action = action.clone();
+        let check = check.clone();
+        let route = route.clone();
+        // an item only opening its nested menu
+        let opens_only = has_children && action.is_none() && check.is_none() && route.is_none();
+        move |e: Event<MouseData>| {
+            // nested menus live inside their parent's element
+            e.stop_propagation();
+            if disabled.get() || long_press.take_fired() {
+                e.prevent_default();
+                return;
+            }
+            if opens_elsewhere(&route, &e) {
+                return;
+            }
+            // the first tap expands the nested menu, the second one activates the item
+            let expands = bar.is_touch() && has_children && !*show_sub_menu.peek();
+            if opens_only || expands {
+                e.prevent_default();
+                show_sub_menu.toggle();
+                return;
+            }
+            activate(&action, &check);
+            if let Some(route) = &route {
+                route.push();
+            }
+            if !keep_open {
+                bar.close();
+            }
+        }
+    };
+
+    let key_handler = {
+        let id = id.clone();
+        let check = check.clone();
+        let route = route.clone();
+        let children = if disabled.get() {
+            vec![]
+        } else {
+            sub_menu_list.clone().unwrap_or_default()
+        };
+        move |e: Event<KeyboardData>| {
+            let key = e.key();
+            let items = level.items;
+            let parent = level.parent.clone();
+            let first_child = nav::first(&children);
+            let mut focus = move |target: Option<String>| {
+                if let Some(target) = target {
+                    bar.focused_menu.set(target);
+                }
+            };
+            let mut open_children = move || {
+                show_sub_menu.set(true);
+                focus(first_child.clone());
+            };
+            // Closes a nested level and goes back to its parent item
+            let leave_level = move || match parent {
+                Some((parent, mut show_parent)) => {
+                    show_parent.set(false);
+                    focus(Some(parent));
+                    true
+                }
+                None => false,
+            };
+            match key {
+                Key::ArrowDown => focus(items.with(|i| nav::step(i, &id, 1))),
+                Key::ArrowUp => focus(items.with(|i| nav::step(i, &id, -1))),
+                Key::Home => focus(items.with(|i| nav::first(i))),
+                Key::End => focus(items.with(|i| nav::last(i))),
+                Key::ArrowRight if !children.is_empty() => open_children(),
+                Key::ArrowRight => bar.move_root(1, true),
+                Key::ArrowLeft => {
+                    if !leave_level() {
+                        bar.move_root(-1, true)
+                    }
+                }
+                Key::Escape => {
+                    if !leave_level() {
+                        bar.close_and_focus_root()
+                    }
+                }
+                Key::Tab => {
+                    bar.close();
+                    return;
+                }
+                ref k if nav::is_activation(k) => {
+                    if !children.is_empty() {
+                        open_children();
+                    } else if !disabled.get() {
+                        activate(&action, &check);
+                        if let Some(route) = &route {
+                            route.push();
+                        }
+                        if !keep_open {
+                            bar.close_and_focus_root();
+                        }
+                    }
+                }
+                ref k => {
+                    let Some(c) = nav::typed_char(k) else { return };
+                    focus(items.with(|i| nav::find_by_char(i, &id, c)));
+                }
+            }
+            // nested menus live inside their parent's element
+            e.stop_propagation();
+            e.prevent_default();
+        }
+    };
+
+    let id_clone_5 = id.clone();
+    let is_disabled = disabled.get();
+    let (is_active, is_current) = route_state(&route, &sub_menu_list);
 
     rsx! {
         div {
             class: "sub_menu",
-            onmounted: move |elem: Event<MountedData>| async move {
+            class: if is_disabled { "disabled" },
+            class: if is_active { "active" },
+            aria_current: is_current.then_some("page"),
+            id: "{id}",
+            role: check.as_ref().map_or("menuitem", |c| c.role()),
+            aria_checked: check.as_ref().map(|c| c.is_checked().to_string()),
+            "data-radio-group": check.as_ref().and_then(|c| c.group()).map(str::to_string),
+            aria_disabled: is_disabled.then_some("true"),
+            aria_haspopup: has_children.then_some("menu"),
+            aria_keyshortcuts: decor.shortcut.clone(),
+            aria_expanded: has_children.then(|| show_sub_menu().to_string()),
+            aria_controls: has_children.then(|| menu_list_id(&id)),
+            tabindex: -1,
+            onmounted: move |elem: Event<MountedData>| {
                 use dioxus::web::WebEventExt;
-                sub_menu.set(Some(elem.as_web_event()));
-                state.width.set(sub_menu().unwrap().get_bounding_client_rect().width());
+                element.set(Some(elem.data()));
+                state.anchor.set(Some(elem.as_web_event()));
+            },
+            onclick: click_handler,
+            onkeydown: key_handler,
+            onpointermove: move |e: Event<PointerData>| {
+                let point = e.client_coordinates();
+                intent.pointer.set((point.x, point.y));
             },
-            onclick: move |_| {
-                if let Some(action) = &action {
-                    action.call()
+            onpointerdown: move |e: Event<PointerData>| {
+                if !is_mouse(&e) && has_children && !disabled.get() {
+                    long_press.start(move || show_sub_menu.set(true));
                 }
-                show.set(false);
             },
-            onmouseenter: move |_| {
-                show_sub_menu.set(true);
+            onpointerup: move |_| long_press.cancel(),
+            onpointercancel: move |_| long_press.cancel(),
+            // a long press would open the context menu of the browser
+            oncontextmenu: move |e: Event<MouseData>| {
+                if bar.is_touch() && has_children {
+                    e.prevent_default();
+                }
             },
-            onmouseleave: move |_| {
-                show_sub_menu.set(false);
+            onpointerenter: move |e: Event<PointerData>| {
+                if !is_mouse(&e) {
+                    return;
+                }
+                if !has_children || disabled.get() {
+                    timer.cancel();
+                    return;
+                }
+                let delay = bar.hover.peek().open_delay;
+                timer.start(delay, Some(intent), move || {
+                    if !*show_sub_menu.peek() {
+                        show_sub_menu.set(true);
+                    }
+                });
+            },
+            onpointerleave: move |e: Event<PointerData>| {
+                long_press.cancel();
+                if !is_mouse(&e) {
+                    return;
+                }
+                let point = e.client_coordinates();
+                let point = (point.x, point.y);
+                intent.pointer.set(point);
+                if *show_sub_menu.peek() {
+                    if let Some(flyout) = state.flyout.peek().as_ref() {
+                        let rect = flyout.get_bounding_client_rect().into();
+                        intent.aim(&id_clone_5, SafeTriangle::new(point, rect));
+                    }
+                }
+                let delay = bar.hover.peek().close_delay;
+                timer.start(delay, Some(intent), move || {
+                    if *show_sub_menu.peek() {
+                        show_sub_menu.set(false);
+                    }
+                });
             },
-            "{label}"
+            {
+                route_link(
+                    &route,
+                    is_disabled,
+                    rsx! {
+                        MenuContent {
+                            label,
+                            decor: decor.clone(),
+                            check: check.clone(),
+                            has_children,
+                            is_root: false,
+                            on_expand: move |_| {
+                                if !disabled.get() {
+                                    show_sub_menu.toggle();
+                                }
+                            },
+                        }
+                    },
+                )
+            }
             if sub_menu_list.is_some() && show_sub_menu() {
-                SubSubMenuWrapper { sub_menu_list }
+                SubSubMenuWrapper { parent: id.clone(), show_sub_menu, sub_menu_list }
             }
         }
     }
 }
 
 #[component]
-fn SubSubMenuWrapper(sub_menu_list: Option<Vec<Menu>>) -> Element {
+fn SubSubMenuWrapper(
+    parent: String,
+    show_sub_menu: Signal<bool>,
+    sub_menu_list: Option<Vec<Menu>>,
+) -> Element {
     let show = use_context::<MenuState>().show;
-    let width = use_context::<SubMenuState>().width;
+    let mut state = use_context::<SubMenuState>();
+    let (mut flyout, position) = use_flyout(state.anchor, show, Placement::Beside);
+    use_menu_level(
+        sub_menu_list.clone().unwrap_or_default(),
+        Some((parent.clone(), show_sub_menu)),
+    );
     rsx! {
         if show() {
             div {
                 z_index: 11,
                 class: "sub_sub_menu_wrapper",
-                left: "{width()}px",
+                id: menu_list_id(&parent),
+                role: "menu",
+                aria_labelledby: "{parent}",
+                style: flyout_style(position()),
+                onmounted: move |elem: Event<MountedData>| {
+                    use dioxus::web::WebEventExt;
+                    flyout.set(Some(elem.as_web_event()));
+                    state.flyout.set(Some(elem.as_web_event()));
+                },
                 for sub_menu in sub_menu_list.unwrap() {
                     {sub_menu.render()}
                 }
@@ -246,39 +1521,288 @@ fn SubSubMenuWrapper(sub_menu_list: Option<Vec<Menu>>) -> Element {
 //////////////////////////////////////////////////////////
 #[component]
 fn BurgerMenuView(
-    label: &'static str,
+    label: MenuLabel,
     id: String,
+    decor: MenuDecor,
+    disabled: MenuFlag,
+    check: Option<Check>,
+    keep_open: bool,
+    route: Option<MenuRoute>,
     sub_menu_list: Option<Vec<Menu>>,
+    loader: Option<ChildrenLoader>,
     action: Option<Action>,
+    /// The content of `Menu::panel`, shown after the children of a root
+    content: Option<Element>,
+    is_root: bool,
 ) -> Element {
+    use_panel_warning(&id, !is_root && content.is_some(), "only roots have one");
+    let content = content.filter(|_| is_root);
     let mut state = use_context_provider(|| MenuState {
         show: Signal::new(false),
+        anchor: Signal::new(None),
     });
-    let mut show = use_context::<BurgerMenuState>().show;
-    let has_children = use_signal(|| sub_menu_list.is_some());
-    let click_handler = move |_: Event<MouseData>| {
+    let burger = use_context::<BurgerMenuState>();
+    let mut show = burger.show;
+    let accordion = (burger.accordion)();
+    let level = use_context::<BurgerLevel>();
+    // the title of the panel listing the children
+    let title = label.get();
+    let sub_menu_list = use_children(&id, sub_menu_list, loader, state.show);
+    let aria_has_children = sub_menu_list.is_some() || content.is_some();
+    let has_children = use_signal(|| aria_has_children);
+    let check_clone = check.clone();
+    let route_clone = route.clone();
+    let has_route = route.is_some();
+    let (is_active, is_current) = route_state(&route, &sub_menu_list);
+    let mut on_activate = move || {
+        if disabled.get() {
+            return;
+        }
         if has_children() {
-            state.show.set(true);
-        } else {
-            if let Some(action) = &action {
-                action.call();
-                show.set(false)
+            if accordion {
+                state.show.toggle();
+            } else {
+                state.show.set(true);
+            }
+        } else if action.is_some() || check_clone.is_some() || has_route {
+            let (action, check, route) = (action.clone(), check_clone.clone(), route_clone.clone());
+            let run = move || {
+                activate(&action, &check);
+                if let Some(route) = &route {
+                    route.push();
+                }
+            };
+            if keep_open {
+                run();
+            } else {
+                show.set(false);
+                // the actions and routes navigating come after the entry of the drawer is dropped
+                after_overlay_entry(run);
             }
         }
     };
     rsx! {
-        div { class: "burger_root_menu",
+        div { class: "burger_root_menu", role: "none",
 
             div {
                 class: "center",
+                class: if disabled.get() { "disabled" },
+                class: if is_active { "active" },
+                class: if accordion && (state.show)() { "expanded" },
+                aria_current: is_current.then_some("page"),
                 id: "{id}",
                 z_index: 10,
-                onclick: click_handler,
-                "{label}"
+                role: check.as_ref().map_or("menuitem", |c| c.role()),
+                aria_checked: check.as_ref().map(|c| c.is_checked().to_string()),
+                "data-radio-group": check.as_ref().and_then(|c| c.group()).map(str::to_string),
+                aria_disabled: disabled.get().then_some("true"),
+                aria_haspopup: aria_has_children.then_some("menu"),
+                aria_keyshortcuts: decor.shortcut.clone(),
+                aria_expanded: aria_has_children.then(|| (state.show)().to_string()),
+                aria_controls: aria_has_children.then(|| menu_list_id(&id)),
+                tabindex: 0,
+                onclick: {
+                    let mut on_activate = on_activate.clone();
+                    let route = route.clone();
+                    move |e: Event<MouseData>| {
+                        if !opens_elsewhere(&route, &e) {
+                            on_activate()
+                        }
+                    }
+                },
+                onkeydown: move |e: Event<KeyboardData>| {
+                    if nav::is_activation(&e.key()) {
+                        e.prevent_default();
+                        on_activate();
+                    }
+                },
+                {
+                    route_link(
+                        &route,
+                        disabled.get(),
+                        rsx! {
+                            MenuContent {
+                                label,
+                                decor: decor.clone(),
+                                check: check.clone(),
+                                has_children: aria_has_children,
+                                is_root: false,
+                            }
+                        },
+                    )
+                }
             }
-            if (state.show)() {
-                BurgerMenuWrapper { show: state.show, menu_list: sub_menu_list.unwrap() }
+            if (state.show)() && accordion {
+                div {
+                    class: "burger_accordion center_y_top",
+                    id: menu_list_id(&id),
+                    role: "menu",
+                    aria_labelledby: "{id}",
+                    for menu in sub_menu_list.unwrap_or_default() {
+                        {menu.render_mob()}
+                    }
+                }
+                if let Some(content) = content {
+                    div { class: "burger_panel_content", {content} }
+                }
+            } else if (state.show)() {
+                BurgerMenuWrapper {
+                    show: state.show,
+                    menu_list: sub_menu_list.unwrap_or_default(),
+                    parent: Some(id.clone()),
+                    trail: [level.trail.clone(), vec![title.clone()]].concat(),
+                    content,
+                }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(menus: &[Menu]) -> Vec<&str> {
+        menus.iter().map(Menu::id).collect()
+    }
+
+    #[test]
+    fn with_ids_numbers_menus_by_position() {
+        let menus = with_ids(
+            vec![
+                Menu::new("File").children(vec![Menu::new("Open"), Menu::new("Save")]),
+                Menu::separator(),
+                Menu::new("Help"),
+            ],
+            "menu",
+        );
+        assert_eq!(ids(&menus), vec!["menu_0", "menu_1", "menu_2"]);
+        assert_eq!(
+            ids(menus[0].sub_menus().unwrap()),
+            vec!["menu_0_0", "menu_0_1"]
+        );
+    }
+
+    #[test]
+    fn with_ids_keeps_keys_and_prefixes_their_children() {
+        let menus = with_ids(
+            vec![
+                Menu::new("File").children(vec![Menu::new("Open"), Menu::new("Save").key("save")]),
+                Menu::new("Help")
+                    .key("help")
+                    .children(vec![Menu::new("About")]),
+            ],
+            "menu",
+        );
+        assert_eq!(ids(&menus), vec!["menu_0", "help"]);
+        assert_eq!(ids(menus[0].sub_menus().unwrap()), vec!["menu_0_0", "save"]);
+        assert_eq!(ids(menus[1].sub_menus().unwrap()), vec!["help_0"]);
+    }
+
+    /// The labels of `menus`, with `---` for separators and `#` before headings
+    fn outline(menus: &[Menu]) -> Vec<String> {
+        menus
+            .iter()
+            .map(|menu| match menu.kind {
+                MenuKind::Separator => "---".to_string(),
+                MenuKind::Heading => format!("# {}", menu.label()),
+                _ => menu.label(),
+            })
+            .collect()
+    }
+
+    fn held(permissions: &[&str]) -> Vec<String> {
+        permissions.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn permitted_needs_every_required_permission() {
+        let menus = vec![
+            Menu::new("Home"),
+            Menu::new("Users").requires("admin"),
+            Menu::new("Audit").requires("admin").requires("audit"),
+        ];
+        let menus = permitted(menus, &held(&["admin"]));
+        assert_eq!(outline(&menus), vec!["Home", "Users"]);
+    }
+
+    #[test]
+    fn permitted_collapses_submenus_left_empty() {
+        let admin_items = || vec![Menu::new("Users").requires("admin")];
+        let menus = vec![
+            Menu::new("Admin").children(admin_items()),
+            Menu::new("Settings").action(|| {}).children(admin_items()),
+            Menu::group("Danger", admin_items()),
+            Menu::new("Recent").children(vec![]),
+            Menu::new("File").children(vec![
+                Menu::new("Open"),
+                Menu::new("Users").requires("admin"),
+            ]),
+        ];
+        let menus = permitted(menus, &[]);
+        // a clickable menu stays, a menu which was empty from the start too
+        assert_eq!(outline(&menus), vec!["Settings", "Recent", "File"]);
+        // and turns into a plain item
+        assert!(menus[0].sub_menus().is_none());
+        assert!(menus[1].sub_menus().unwrap().is_empty());
+        assert_eq!(outline(menus[2].sub_menus().unwrap()), vec!["Open"]);
+    }
+
+    #[test]
+    fn permitted_drops_orphaned_separators() {
+        let menus = vec![
+            Menu::separator(),
+            Menu::new("Open"),
+            Menu::separator(),
+            Menu::new("Users").requires("admin"),
+            Menu::separator(),
+            Menu::new("Quit"),
+            Menu::separator(),
+            Menu::new("Audit").requires("admin"),
+        ];
+        let menus = permitted(menus, &[]);
+        assert_eq!(outline(&menus), vec!["Open", "---", "Quit"]);
+    }
+
+    #[test]
+    fn permitted_drops_headings_with_nothing_under_them() {
+        let menus = vec![
+            Menu::heading("Admin"),
+            Menu::new("Users").requires("admin"),
+            Menu::separator(),
+            Menu::heading("File"),
+            Menu::new("Open"),
+            Menu::heading("Danger"),
+            Menu::new("Delete").requires("admin"),
+        ];
+        let menus = permitted(menus, &[]);
+        assert_eq!(outline(&menus), vec!["# File", "Open"]);
+
+        let menus = permitted(
+            vec![Menu::heading("Admin"), Menu::new("Users").requires("admin")],
+            &held(&["admin"]),
+        );
+        assert_eq!(outline(&menus), vec!["# Admin", "Users"]);
+    }
+
+    fn cache_key(menu: &Menu) -> String {
+        menu.loader.as_ref().unwrap().cache_key()
+    }
+
+    #[test]
+    fn loaded_children_are_cached_by_key_or_by_loader() {
+        let recent = || Menu::new("Recent").children_async(|| async { vec![Menu::new("a.txt")] });
+        assert_eq!(cache_key(&recent().key("recent")), "recent");
+        let keyed_first = Menu::new("Recent")
+            .key("recent")
+            .children_async(|| async { vec![Menu::new("a.txt")] });
+        assert_eq!(cache_key(&keyed_first), "recent");
+
+        // the ids of the bar and of the burger differ, the cache key does not
+        let bar = with_ids(vec![recent()], "menu");
+        let burger = with_ids(vec![recent()], "burger");
+        assert_ne!(bar[0].id(), burger[0].id());
+        assert_eq!(cache_key(&bar[0]), cache_key(&burger[0]));
+        assert_ne!(cache_key(&bar[0]), cache_key(&keyed_first));
+    }
+}
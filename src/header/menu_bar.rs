@@ -1,23 +1,236 @@
 use dioxus::{logger::tracing::info, prelude::*};
 
-use super::menu::Menu;
+use super::controller::{use_controller, MenuController};
+use super::hover::{HoverConfig, HoverTimer, OpenOn};
+use super::menu::{permitted, with_ids, Menu, MenuPermissions};
+use super::pointer::PointerKind;
 
 #[derive(Clone, Copy)]
 pub(crate) struct MenuBarState {
     pub opened_menu: Signal<String>,
+    /// The id of the menu item holding the keyboard focus
+    pub focused_menu: Signal<String>,
+    pub roots: Signal<Vec<Menu>>,
+    /// The ids of the open nested menus
+    pub expanded: Signal<Vec<String>>,
+    /// The ids of the nested menus the `MenuController` asked to open
+    pub expand_request: Signal<Vec<String>>,
+    pub hover: Signal<HoverConfig>,
+    /// The pending hover open or close of a root
+    pub timer: HoverTimer,
+    /// The pointer of the last press inside the bar
+    pub pointer: Signal<PointerKind>,
+    /// The bar element, mega menus span its width
+    pub element: Signal<Option<web_sys::Element>>,
 }
 
+impl MenuBarState {
+    pub fn new(controller: MenuController, hover: HoverConfig) -> Self {
+        Self {
+            opened_menu: controller.opened_menu,
+            focused_menu: controller.focused_menu,
+            roots: controller.roots,
+            expanded: controller.expanded,
+            expand_request: controller.expand_request,
+            hover: Signal::new(hover),
+            timer: HoverTimer::new(),
+            pointer: Signal::new(PointerKind::Mouse),
+            element: Signal::new(None),
+        }
+    }
+
+    pub fn open(&mut self, id: &str) {
+        self.timer.cancel();
+        self.opened_menu.set(id.to_string());
+    }
+
+    pub fn close(&mut self) {
+        self.timer.cancel();
+        self.opened_menu.set("".to_string());
+        if !self.expand_request.peek().is_empty() {
+            self.expand_request.set(vec![]);
+        }
+    }
+
+    /// Opens `id` once the pointer rested on it for the open delay,
+    /// right away when another root is already open.
+    pub fn hover_open(&mut self, id: &str) {
+        if self.is_open(id) {
+            self.timer.cancel();
+            return;
+        }
+        let delay = if self.opened_menu.peek().is_empty() {
+            self.hover.peek().open_delay
+        } else {
+            0
+        };
+        let mut opened_menu = self.opened_menu;
+        let id = id.to_string();
+        self.timer.start(delay, None, move || opened_menu.set(id));
+    }
+
+    /// Closes the opened root after the close delay, unless the pointer comes back.
+    pub fn hover_close(&mut self) {
+        if self.opened_menu.peek().is_empty() {
+            self.timer.cancel();
+            return;
+        }
+        let mut bar = *self;
+        let delay = self.hover.peek().close_delay;
+        self.timer.start(delay, None, move || {
+            bar.close();
+        });
+    }
+
+    pub fn open_on(&self) -> OpenOn {
+        self.hover.peek().open_on
+    }
+
+    /// Remembers the kind of pointer pressing, before the click of any item
+    pub fn set_pointer(&mut self, e: &Event<PointerData>) {
+        let pointer = PointerKind::of(&e.pointer_type());
+        if *self.pointer.peek() != pointer {
+            self.pointer.set(pointer);
+        }
+    }
+
+    /// Whether the click being handled comes from a tap
+    pub fn is_touch(&self) -> bool {
+        *self.pointer.peek() == PointerKind::Touch
+    }
+
+    pub fn is_open(&self, id: &str) -> bool {
+        self.opened_menu.with(|m| m == id)
+    }
+
+    /// Closes the opened menu and gives the focus back to its root.
+    pub fn close_and_focus_root(&mut self) {
+        let root = self.opened_menu.peek().clone();
+        self.close();
+        if !root.is_empty() {
+            self.focused_menu.set(root);
+        }
+    }
+
+    /// The root reachable by `Tab`: the focused root, or the first one.
+    pub fn tab_stop(&self) -> Option<String> {
+        let focused = (self.focused_menu)();
+        self.roots.with(|r| {
+            if r.iter().any(|m| m.id() == focused) {
+                Some(focused)
+            } else {
+                super::nav::first(r)
+            }
+        })
+    }
+
+    /// Moves the focus `step` roots away from the opened (or focused) one.
+    /// The new root gets opened if `open` is true.
+    pub fn move_root(&mut self, step: isize, open: bool) {
+        let current = if self.opened_menu.peek().is_empty() {
+            self.focused_menu.peek().clone()
+        } else {
+            self.opened_menu.peek().clone()
+        };
+        if let Some(next) = self.roots.with(|r| super::nav::step(r, &current, step)) {
+            if open {
+                self.open(&next);
+            } else {
+                self.close();
+            }
+            self.focused_menu.set(next);
+        }
+    }
+}
+
+/// A horizontal menu bar.
+///
+/// With `OpenOn::Hover` the roots open after the pointer rests `open_delay` ms on them
+/// and close `close_delay` ms after it left, nested menus use the same delays.
+///
+/// `on_open_change` gets the key of the opened root, `None` once closed.
+/// Menus without a key get ids starting with `id_prefix`, change it when several bars share a page.
+/// With `permissions`, only the menus whose `Menu::requires` they hold are shown,
+/// async children included once loaded.
+/// Use `use_menu_bar` above it to open and close the menus from the app.
+///
+/// ```
+/// MenuBar { menu_list, open_on: OpenOn::Click }
+/// ```
 #[component]
-pub fn MenuBar(menu_list: Vec<Menu>) -> Element {
+pub fn MenuBar(
+    menu_list: Vec<Menu>,
+    #[props(default)] open_on: OpenOn,
+    #[props(default = 100)] open_delay: u64,
+    #[props(default = 300)] close_delay: u64,
+    on_open_change: Option<EventHandler<Option<String>>>,
+    #[props(into, default = "menu".to_string())] id_prefix: String,
+    #[props(into, default)] permissions: MenuPermissions,
+) -> Element {
     const HEADER_CLASS: Asset = asset!("/assets/header.css");
     const MAIN_CSS: Asset = asset!("/assets/main.css");
-    use_context_provider(|| MenuBarState {
-        opened_menu: Signal::new("".to_string()),
+    // ids first, so they do not depend on the permissions
+    let mut menu_list = with_ids(menu_list, &id_prefix);
+    if let Some(held) = permissions.get() {
+        menu_list = permitted(menu_list, &held);
+    }
+    let mut controller = use_controller();
+    use_hook(|| {
+        if *controller.roots.peek() != menu_list {
+            controller.roots.set(menu_list.clone());
+        }
+    });
+    let mut state = use_context_provider(|| {
+        let hover = HoverConfig {
+            open_on,
+            open_delay,
+            close_delay,
+        };
+        MenuBarState::new(controller, hover)
+    });
+    use_context_provider(|| controller.children);
+    use_effect(use_reactive((&menu_list,), move |(menu_list,)| {
+        if *state.roots.peek() != menu_list {
+            state.roots.set(menu_list);
+        }
+    }));
+    use_effect(use_reactive(
+        (&open_on, &open_delay, &close_delay),
+        move |(open_on, open_delay, close_delay)| {
+            let hover = HoverConfig {
+                open_on,
+                open_delay,
+                close_delay,
+            };
+            if *state.hover.peek() != hover {
+                state.hover.set(hover);
+            }
+        },
+    ));
+    let mut last_opened = use_signal(String::new);
+    use_effect(move || {
+        let opened = (state.opened_menu)();
+        if *last_opened.peek() == opened {
+            return;
+        }
+        last_opened.set(opened.clone());
+        if let Some(on_open_change) = on_open_change {
+            on_open_change.call((!opened.is_empty()).then_some(opened));
+        }
     });
     rsx! {
         document::Stylesheet { href: "{MAIN_CSS}" }
         document::Stylesheet { href: "{HEADER_CLASS}" }
-        div { class: "menu_bar",
+        div {
+            class: "menu_bar",
+            role: "menubar",
+            aria_orientation: "horizontal",
+            onmounted: move |elem: Event<MountedData>| {
+                use dioxus::web::WebEventExt;
+                state.element.set(Some(elem.as_web_event()));
+            },
+            // runs before the click of any item, nested ones included
+            onpointerdown: move |e: Event<PointerData>| state.set_pointer(&e),
             for menu in menu_list {
                 {menu.render()}
             }
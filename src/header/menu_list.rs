@@ -1,56 +1,300 @@
 use dioxus::{logger::tracing::info, prelude::*};
+use std::time::Duration;
 
-use super::menu::Menu;
+use super::controller::use_controller;
+use super::menu::{menu_list_id, permitted, with_ids, Menu, MenuPermissions};
+use super::nav;
+use super::overlay::{
+    focus_element, last_of, trap_tab, use_close_on_back, use_scroll_lock, use_swipe,
+};
 use crate::icon;
 
+/// The side the burger drawer slides in from
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DrawerSide {
+    #[default]
+    Left,
+    Right,
+}
+
+impl DrawerSide {
+    fn class(&self) -> &'static str {
+        match self {
+            DrawerSide::Left => "left",
+            DrawerSide::Right => "right",
+        }
+    }
+
+    /// The direction the drawer is swiped to close it
+    fn direction(&self) -> f64 {
+        match self {
+            DrawerSide::Left => -1.0,
+            DrawerSide::Right => 1.0,
+        }
+    }
+}
+
+/// How long a nested panel takes to slide out, in ms, see `.burger_panel.leaving`
+const PANEL_OUT: u64 = 200;
+/// How long the drawer takes to slide out, in ms, see `.burger_drawer.closing`
+const DRAWER_OUT: u64 = 200;
+
 #[derive(Clone, Copy)]
 pub struct BurgerMenuState {
     pub show: Signal<bool>,
+    /// Children expand inline instead of opening a panel
+    pub(crate) accordion: Signal<bool>,
 }
 
+/// The labels from the root list down to the panel being rendered, shown as its breadcrumb
+#[derive(Clone, PartialEq)]
+pub(crate) struct BurgerLevel {
+    pub trail: Vec<String>,
+}
+
+/// The burger menu, `on_open_change` tells when it opens or closes.
+/// Use `use_menu_bar` above it to open and close it from the app.
+/// Menus without a key get ids starting with `id_prefix` and are filtered by `permissions`, like in `MenuBar`.
+/// The prefix differs from the one of `MenuBar`, so both can render the same menus on a page.
+///
+/// The menus open in a drawer sliding in from `side`. Menus with children open a panel
+/// sliding over the list, with a back button and the path to it,
+/// or with `accordion` they expand their children below themselves.
+///
+/// While open, the page does not scroll and `Tab` stays in the drawer. It closes on Escape,
+/// the back button of the browser and a swipe towards its side, then the focus
+/// goes back to the burger button.
 #[component]
-pub fn MenuList(menu_list: Vec<Menu>) -> Element {
+pub fn MenuList(
+    menu_list: Vec<Menu>,
+    on_open_change: Option<EventHandler<bool>>,
+    #[props(into, default = "burger".to_string())] id_prefix: String,
+    #[props(into, default)] permissions: MenuPermissions,
+    #[props(default)] side: DrawerSide,
+    #[props(default)] accordion: bool,
+) -> Element {
     const HEADER_CLASS: Asset = asset!("/assets/header.css");
     const MAIN_CSS: Asset = asset!("/assets/main.css");
+    let mut menu_list = with_ids(menu_list, &id_prefix);
+    if let Some(held) = permissions.get() {
+        menu_list = permitted(menu_list, &held);
+    }
+    let controller = use_controller();
     let mut state = use_context_provider(|| BurgerMenuState {
-        show: Signal::new(false),
+        show: controller.burger,
+        accordion: Signal::new(accordion),
+    });
+    use_context_provider(|| controller.children);
+    use_effect(use_reactive((&accordion,), move |(accordion,)| {
+        if *state.accordion.peek() != accordion {
+            state.accordion.set(accordion);
+        }
+    }));
+    use_scroll_lock(state.show);
+    use_close_on_back(state.show);
+    let mut swipe = use_swipe();
+    let mut drawer: Signal<Option<web_sys::Element>> = use_signal(|| None);
+    let mut was_open = use_signal(|| false);
+    // the drawer stays while sliding out
+    let mut rendered = use_signal(|| false);
+    let mut closing = use_signal(|| false);
+    use_effect(move || {
+        let open = (state.show)();
+        if *was_open.peek() == open {
+            return;
+        }
+        was_open.set(open);
+        if open {
+            closing.set(false);
+            rendered.set(true);
+        } else {
+            closing.set(true);
+            focus_element("burgerMenu");
+            spawn(async move {
+                gloo_timers::future::sleep(Duration::from_millis(DRAWER_OUT)).await;
+                // opened again meanwhile
+                if !*state.show.peek() {
+                    rendered.set(false);
+                    closing.set(false);
+                }
+            });
+        }
+        if let Some(on_open_change) = on_open_change {
+            on_open_change.call(open);
+        }
     });
+    let drag = (swipe.offset)();
+    let drag_style =
+        (drag != 0.0).then(|| format!("transform: translateX({drag}px); transition: none;"));
     rsx! {
         document::Stylesheet { href: "{MAIN_CSS}" }
         document::Stylesheet { href: "{HEADER_CLASS}" }
-        div { id: "burgerMenu", onclick: move |_| state.show.set(true), {icon!(LdMenu)} }
-        if (state.show)() && !menu_list.is_empty() {
-            BurgerMenuWrapper { show: state.show, menu_list }
+        div {
+            id: "burgerMenu",
+            role: "button",
+            tabindex: 0,
+            aria_label: "Open menu",
+            aria_haspopup: "menu",
+            aria_expanded: "{(state.show)()}",
+            aria_controls: "BurgerMenuWrapper",
+            onclick: move |_| state.show.set(true),
+            onkeydown: move |e: Event<KeyboardData>| {
+                if nav::is_activation(&e.key()) {
+                    e.prevent_default();
+                    state.show.set(true);
+                }
+            },
+            {icon!(LdMenu)}
+        }
+        if rendered() && !menu_list.is_empty() {
+            div {
+                class: "burger_backdrop",
+                class: if closing() { "closing" },
+                z_index: 9,
+                aria_hidden: "true",
+                onclick: move |_| state.show.set(false),
+            }
+            div {
+                z_index: 10,
+                class: "burger_drawer {side.class()}",
+                class: if closing() { "closing" },
+                style: drag_style,
+                onmounted: move |elem: Event<MountedData>| {
+                    use dioxus::web::WebEventExt;
+                    drawer.set(Some(elem.as_web_event()));
+                },
+                onkeydown: move |e: Event<KeyboardData>| {
+                    match e.key() {
+                        Key::Escape => state.show.set(false),
+                        Key::Tab => {
+                            // only the panel on top is reachable
+                            let panel = drawer.peek().as_ref().and_then(|d| last_of(d, ".burger_panel"));
+                            if !panel.is_some_and(|p| trap_tab(&p, e.modifiers().shift())) {
+                                return;
+                            }
+                        }
+                        _ => return,
+                    }
+                    e.prevent_default();
+                },
+                onpointerdown: move |e: Event<PointerData>| swipe.start(&e),
+                onpointermove: move |e: Event<PointerData>| swipe.drag(&e, side.direction()),
+                onpointerup: move |_| {
+                    if swipe.end() {
+                        state.show.set(false);
+                    }
+                },
+                onpointercancel: move |_| {
+                    swipe.end();
+                },
+                BurgerMenuWrapper { show: state.show, menu_list }
+            }
         }
     }
 }
 
+/// One panel of the burger drawer.
+/// `parent` is the id of the menu whose children are listed, `None` for the root list,
+/// and `trail` the labels from the root list down to that menu.
 #[component]
-pub fn BurgerMenuWrapper(show: Signal<bool>, menu_list: Vec<Menu>) -> Element {
+pub fn BurgerMenuWrapper(
+    show: Signal<bool>,
+    menu_list: Vec<Menu>,
+    parent: Option<String>,
+    #[props(default)] trail: Vec<String>,
+    /// Shown after the menus, see `Menu::panel`
+    #[props(default)]
+    content: Option<Element>,
+) -> Element {
+    use_context_provider(|| BurgerLevel {
+        trail: trail.clone(),
+    });
+    let mut leaving = use_signal(|| false);
+    let list_id = parent
+        .as_deref()
+        .map(menu_list_id)
+        .unwrap_or_else(|| "BurgerMenuWrapper_list".to_string());
+    let is_nested = parent.is_some();
+    let parent_clone = parent.clone();
+    // the root list closes the drawer, a nested one slides back to its parent
+    let mut back = move || {
+        if !is_nested {
+            show.set(false);
+            return;
+        }
+        if leaving() {
+            return;
+        }
+        leaving.set(true);
+        let parent = parent_clone.clone();
+        spawn(async move {
+            gloo_timers::future::sleep(Duration::from_millis(PANEL_OUT)).await;
+            leaving.set(false);
+            show.set(false);
+            if let Some(parent) = parent {
+                focus_element(&parent);
+            }
+        });
+    };
+    let title = trail.last().cloned();
+    let ancestors = trail.len().saturating_sub(1);
     rsx! {
         div {
-            z_index: 10,
-            class: "fixed center_y_top",
-            id: "BurgerMenuWrapper",
-            //// pick the first menu and check if root to define the icon: x or arrow
-            if let Some(menu) = menu_list.get(0) {
-                div { class: "icon_wrapper",
-                    div { class: "icon", onclick: move |_| show.set(false),
-                        {
-                            if menu.is_root() {
-                                icon!(LdX, 40)
-                            } else {
-                                icon!(LdCornerDownLeft, 40, "white", "purple")
-                            }
+            class: "burger_panel",
+            class: if is_nested { "nested" },
+            class: if leaving() { "leaving" },
+            id: if !is_nested { "BurgerMenuWrapper" },
+            div { class: "burger_panel_header icon_wrapper",
+                div {
+                    class: "icon",
+                    role: "button",
+                    tabindex: 0,
+                    aria_label: if is_nested { "Back" } else { "Close menu" },
+                    onmounted: move |elem: Event<MountedData>| async move {
+                        _ = elem.data().set_focus(true).await;
+                    },
+                    onclick: {
+                        let mut back = back.clone();
+                        move |_| back()
+                    },
+                    onkeydown: move |e: Event<KeyboardData>| {
+                        if nav::is_activation(&e.key()) {
+                            e.prevent_default();
+                            back();
+                        }
+                    },
+                    {
+                        if is_nested {
+                            icon!(LdChevronLeft, 32, "none", "currentColor")
+                        } else {
+                            icon!(LdX, 40)
                         }
                     }
                 }
+                if let Some(title) = title {
+                    nav { class: "burger_breadcrumb", aria_label: "Breadcrumb",
+                        for label in trail.iter().take(ancestors) {
+                            span { class: "burger_breadcrumb_item", "{label}" }
+                            span { class: "burger_breadcrumb_separator", aria_hidden: "true", "›" }
+                        }
+                        span { class: "burger_breadcrumb_title", aria_current: "location", "{title}" }
+                    }
+                }
             }
             div {
+                class: "center_y_top",
+                id: list_id,
+                role: "menu",
+                aria_orientation: "vertical",
+                aria_label: if parent.is_none() { Some("Menu") } else { None },
+                aria_labelledby: parent.clone(),
                 for menu in menu_list {
                     {menu.render_mob()}
                 }
             }
+            if let Some(content) = content {
+                div { class: "burger_panel_content", {content} }
+            }
         }
     }
 }
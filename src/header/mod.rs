@@ -11,6 +11,55 @@ pub use menu_list::*;
 mod menu;
 pub use menu::*;
 
+mod context_menu;
+pub use context_menu::*;
+
+mod dropdown;
+pub use dropdown::*;
+
+mod mega;
+
+mod palette;
+pub use palette::CommandPalette;
+
+mod side_nav;
+pub use side_nav::SideNav;
+
+mod nav;
+
+mod controller;
+pub use controller::{use_menu_bar, MenuController};
+
+mod placement;
+pub use placement::Placement;
+
+mod hover;
+pub use hover::OpenOn;
+
+mod pointer;
+
+mod overlay;
+
+mod storage;
+
+mod check;
+
+mod lazy;
+pub use lazy::IntoChildren;
+
+mod label;
+pub use label::*;
+
+mod route;
+
+#[cfg(feature = "serde")]
+mod load;
+#[cfg(feature = "serde")]
+pub use load::*;
+
+mod responsive;
+pub use responsive::*;
+
 // mod sub_menu;
 // pub use sub_menu::*;
 
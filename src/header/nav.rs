@@ -0,0 +1,134 @@
+use super::hover::HoverIntent;
+use super::menu::Menu;
+use dioxus::prelude::*;
+use std::rc::Rc;
+
+/// The items of one opened dropdown level, used to move the focus between siblings.
+///
+/// `parent` holds the id of the item which opened this level and the flag keeping it open.
+/// It is `None` for the first dropdown under a root menu.
+#[derive(Clone)]
+pub(crate) struct MenuLevel {
+    pub items: Signal<Vec<Menu>>,
+    pub parent: Option<(String, Signal<bool>)>,
+    /// Where the pointer is heading within this level
+    pub intent: HoverIntent,
+}
+
+/// Provides a `MenuLevel` for `menu_list` and keeps it in sync when the list changes.
+pub(crate) fn use_menu_level(
+    menu_list: Vec<Menu>,
+    parent: Option<(String, Signal<bool>)>,
+) -> MenuLevel {
+    let mut level = use_context_provider(|| MenuLevel {
+        items: Signal::new(menu_list.clone()),
+        parent,
+        intent: HoverIntent::new(),
+    });
+    use_effect(use_reactive((&menu_list,), move |(menu_list,)| {
+        if *level.items.peek() != menu_list {
+            level.items.set(menu_list);
+        }
+    }));
+    level
+}
+
+/// Moves the real DOM focus to the element once `focused_menu` points to `id`.
+///
+/// Returns the signal the element should store itself in from `onmounted`.
+pub(crate) fn use_menu_focus(
+    id: String,
+    focused_menu: Signal<String>,
+) -> Signal<Option<Rc<MountedData>>> {
+    let element: Signal<Option<Rc<MountedData>>> = use_signal(|| None);
+    use_effect(move || {
+        if focused_menu.with(|f| f == &id) {
+            if let Some(element) = element() {
+                spawn(async move {
+                    _ = element.set_focus(true).await;
+                });
+            }
+        }
+    });
+    element
+}
+
+/// The ids from a root down to the menu `id`, groups are skipped as they open nothing.
+pub(crate) fn path_to(items: &[Menu], id: &str) -> Option<Vec<String>> {
+    for menu in items {
+        if menu.id() == id {
+            return Some(vec![id.to_string()]);
+        }
+        let Some(mut path) = menu.sub_menus().and_then(|m| path_to(m, id)) else {
+            continue;
+        };
+        if !menu.is_group() {
+            path.insert(0, menu.id().to_string());
+        }
+        return Some(path);
+    }
+    None
+}
+
+/// The items the focus can land on, groups are flattened into their items.
+fn focusable(items: &[Menu]) -> Vec<&Menu> {
+    let mut list = vec![];
+    for menu in items {
+        if let Some(group_items) = menu.group_items() {
+            list.extend(focusable(group_items));
+        } else if menu.is_focusable() {
+            list.push(menu);
+        }
+    }
+    list
+}
+
+pub(crate) fn first(items: &[Menu]) -> Option<String> {
+    focusable(items).first().map(|m| m.id().to_string())
+}
+
+pub(crate) fn last(items: &[Menu]) -> Option<String> {
+    focusable(items).last().map(|m| m.id().to_string())
+}
+
+/// The focusable item `step` positions away from `id`, wrapping around both ends.
+pub(crate) fn step(items: &[Menu], id: &str, step: isize) -> Option<String> {
+    let items = focusable(items);
+    if items.is_empty() {
+        return None;
+    }
+    let len = items.len() as isize;
+    let index = items.iter().position(|m| m.id() == id).unwrap_or(0) as isize;
+    let next = (index + step).rem_euclid(len) as usize;
+    Some(items[next].id().to_string())
+}
+
+/// The next focusable item after `id` whose label starts with `c` (typeahead).
+pub(crate) fn find_by_char(items: &[Menu], id: &str, c: char) -> Option<String> {
+    let items = focusable(items);
+    let start = items.iter().position(|m| m.id() == id).map_or(0, |i| i + 1);
+    let c = c.to_lowercase().to_string();
+    (0..items.len())
+        .map(|i| items[(start + i) % items.len()])
+        .find(|m| m.label().to_lowercase().starts_with(&c))
+        .map(|m| m.id().to_string())
+}
+
+/// Extracts the printable character of a key event, used for typeahead.
+pub(crate) fn typed_char(key: &Key) -> Option<char> {
+    match key {
+        Key::Character(s) => {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if !c.is_whitespace() => Some(c),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// `Enter` or `Space`
+pub(crate) fn is_activation(key: &Key) -> bool {
+    matches!(key, Key::Enter) || matches!(key, Key::Character(s) if s == " ")
+}
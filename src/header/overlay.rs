@@ -0,0 +1,258 @@
+use dioxus::prelude::*;
+use std::{cell::Cell, rc::Rc};
+use wasm_bindgen::{prelude::Closure, JsCast, JsValue};
+use web_sys::{window, CssStyleDeclaration, HtmlElement};
+
+use super::pointer::is_mouse;
+
+/// The state of the history entry pushed while an overlay is open
+const HISTORY_MARKER: &str = "sam_ui_overlay";
+
+/// How far a finger has to drag a drawer towards its side to close it, in px
+const SWIPE_CLOSE: f64 = 80.0;
+
+/// How far a finger moves before a drag is told apart from a scroll, in px
+const SWIPE_START: f64 = 10.0;
+
+/// What `Tab` can land on
+const FOCUSABLE: &str = "a[href], button:not([disabled]), input:not([disabled]), select, textarea, [tabindex]:not([tabindex='-1'])";
+
+fn body_style() -> Option<CssStyleDeclaration> {
+    Some(window()?.document()?.body()?.style())
+}
+
+/// Keeps the page behind an overlay from scrolling while `open` is true.
+pub(crate) fn use_scroll_lock(open: Signal<bool>) {
+    // the overflow of the body before the lock
+    let mut saved: Signal<Option<String>> = use_signal(|| None);
+    use_effect(move || {
+        let Some(style) = body_style() else {
+            return;
+        };
+        if open() {
+            if saved.peek().is_none() {
+                saved.set(Some(
+                    style.get_property_value("overflow").unwrap_or_default(),
+                ));
+                _ = style.set_property("overflow", "hidden");
+            }
+        } else if let Some(previous) = saved.take() {
+            _ = style.set_property("overflow", &previous);
+        }
+    });
+    use_drop(move || {
+        let previous = saved.try_write().ok().and_then(|mut s| s.take());
+        if let (Some(previous), Some(style)) = (previous, body_style()) {
+            _ = style.set_property("overflow", &previous);
+        }
+    });
+}
+
+/// Calls `callback` on every `event` of the window.
+pub(crate) fn use_window_listener(event: &'static str, mut callback: impl FnMut() + 'static) {
+    use_window_event(event, move |_| callback());
+}
+
+/// Calls `callback` with every `event` of the window, e.g. to read the keys of a `keydown`.
+pub(crate) fn use_window_event(
+    event: &'static str,
+    callback: impl FnMut(web_sys::Event) + 'static,
+) {
+    let closure = use_hook(|| {
+        let closure = Closure::wrap(Box::new(callback) as Box<dyn FnMut(web_sys::Event)>);
+        window()?
+            .add_event_listener_with_callback(event, closure.as_ref().unchecked_ref())
+            .ok()?;
+        Some(Rc::new(closure))
+    });
+
+    use_drop(move || {
+        if let (Some(closure), Some(win)) = (closure, window()) {
+            win.remove_event_listener_with_callback(
+                event,
+                closure.as_ref().as_ref().unchecked_ref(),
+            )
+            .ok();
+        }
+    });
+}
+
+/// Whether the current history entry is the one pushed by `use_close_on_back`
+fn is_overlay_entry() -> bool {
+    window()
+        .and_then(|w| w.history().ok())
+        .and_then(|h| h.state().ok())
+        .and_then(|s| s.as_string())
+        .is_some_and(|s| s == HISTORY_MARKER)
+}
+
+/// Pushes a history entry while `open` is true, so the back button of the browser
+/// closes the overlay instead of leaving the page.
+pub(crate) fn use_close_on_back(mut open: Signal<bool>) {
+    let mut pushed = use_signal(|| false);
+    use_effect(move || {
+        let Some(history) = window().and_then(|w| w.history().ok()) else {
+            return;
+        };
+        if open() {
+            let marker = JsValue::from_str(HISTORY_MARKER);
+            if !*pushed.peek() && history.push_state(&marker, "").is_ok() {
+                pushed.set(true);
+            }
+        } else if *pushed.peek() {
+            pushed.set(false);
+            // closed from the page: drops the entry, see `after_overlay_entry`
+            if is_overlay_entry() {
+                _ = history.back();
+            }
+        }
+    });
+    use_window_listener("popstate", move || {
+        if *open.peek() {
+            pushed.set(false);
+            open.set(false);
+        }
+    });
+}
+
+/// Runs `then` once the entry pushed by `use_close_on_back` is dropped, right away
+/// when it is not the current one. Items closing the overlay go through it,
+/// so the routes they push come after the page, not after an entry left behind.
+pub(crate) fn after_overlay_entry(then: impl FnOnce() + 'static) {
+    let Some(win) = window().filter(|_| is_overlay_entry()) else {
+        then();
+        return;
+    };
+    let then = Rc::new(Cell::new(Some(then)));
+    let run = move || {
+        if let Some(then) = then.take() {
+            then();
+        }
+    };
+    let options = web_sys::AddEventListenerOptions::new();
+    options.set_once(true);
+    let added = win.add_event_listener_with_callback_and_add_event_listener_options(
+        "popstate",
+        Closure::once_into_js(run.clone()).unchecked_ref(),
+        &options,
+    );
+    if added.is_err() {
+        run();
+    }
+}
+
+/// The elements `Tab` can land on inside `container`, with the index of the focused one
+fn focusables(container: &web_sys::Element) -> (Vec<HtmlElement>, Option<usize>) {
+    let Ok(nodes) = container.query_selector_all(FOCUSABLE) else {
+        return (vec![], None);
+    };
+    let items: Vec<HtmlElement> = (0..nodes.length())
+        .filter_map(|i| nodes.item(i))
+        .filter_map(|n| n.dyn_into::<HtmlElement>().ok())
+        .collect();
+    let active = window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.active_element());
+    let index = active.and_then(|a| items.iter().position(|i| **i == a));
+    (items, index)
+}
+
+/// Wraps the focus around the ends of `container` on `Tab`, or brings it back inside.
+/// Returns whether it moved the focus, the key should then be prevented.
+pub(crate) fn trap_tab(container: &web_sys::Element, backwards: bool) -> bool {
+    let (items, index) = focusables(container);
+    let (Some(first), Some(last)) = (items.first(), items.last()) else {
+        return false;
+    };
+    let target = match (index, backwards) {
+        (None, false) => first,
+        (None, true) => last,
+        (Some(0), true) => last,
+        (Some(i), false) if i == items.len() - 1 => first,
+        _ => return false,
+    };
+    _ = target.focus();
+    true
+}
+
+/// Whether `Tab` moves the focus out of `container`, from its first or last focusable element
+pub(crate) fn tab_leaves(container: &web_sys::Element, backwards: bool) -> bool {
+    let (items, index) = focusables(container);
+    match (index, backwards) {
+        (Some(0), true) => true,
+        (Some(i), false) => i == items.len() - 1,
+        _ => false,
+    }
+}
+
+/// The last element matching `selector` inside `container`
+pub(crate) fn last_of(container: &web_sys::Element, selector: &str) -> Option<web_sys::Element> {
+    let nodes = container.query_selector_all(selector).ok()?;
+    nodes.item(nodes.length().checked_sub(1)?)?.dyn_into().ok()
+}
+
+/// Moves the focus to the element `id`, if it can hold it.
+pub(crate) fn focus_element(id: &str) {
+    let element = window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.get_element_by_id(id))
+        .and_then(|e| e.dyn_into::<HtmlElement>().ok());
+    if let Some(element) = element {
+        _ = element.focus();
+    }
+}
+
+/// A finger dragging a drawer towards its side, `offset` follows it in px.
+#[derive(Clone, Copy)]
+pub(crate) struct Swipe {
+    start: Signal<Option<(f64, f64)>>,
+    pub offset: Signal<f64>,
+}
+
+impl Swipe {
+    pub fn start(&mut self, e: &Event<PointerData>) {
+        if is_mouse(e) {
+            return;
+        }
+        let point = e.client_coordinates();
+        self.start.set(Some((point.x, point.y)));
+    }
+
+    /// Follows the finger, `direction` is -1.0 for a drawer closing to the left, 1.0 to the right
+    pub fn drag(&mut self, e: &Event<PointerData>, direction: f64) {
+        let Some((x, y)) = *self.start.peek() else {
+            return;
+        };
+        let point = e.client_coordinates();
+        let (dx, dy) = (point.x - x, point.y - y);
+        if *self.offset.peek() == 0.0 {
+            if dx.abs().max(dy.abs()) < SWIPE_START {
+                return;
+            }
+            // a vertical move scrolls the list
+            if dy.abs() > dx.abs() {
+                self.start.set(None);
+                return;
+            }
+        }
+        self.offset.set((dx * direction).max(0.0) * direction);
+    }
+
+    /// Ends the drag, returns whether it went far enough to close the drawer
+    pub fn end(&mut self) -> bool {
+        let far = self.offset.peek().abs() >= SWIPE_CLOSE;
+        if self.start.peek().is_some() {
+            self.start.set(None);
+        }
+        if *self.offset.peek() != 0.0 {
+            self.offset.set(0.0);
+        }
+        far
+    }
+}
+
+pub(crate) fn use_swipe() -> Swipe {
+    let start = use_signal(|| None);
+    let offset = use_signal(|| 0.0);
+    Swipe { start, offset }
+}
@@ -0,0 +1,529 @@
+use dioxus::prelude::*;
+use std::cmp::Reverse;
+use wasm_bindgen::JsCast;
+use web_sys::{window, HtmlElement, KeyboardEvent, ScrollIntoViewOptions, ScrollLogicalPosition};
+
+use super::check::Check;
+use super::menu::{activate, commands, permitted, Menu, MenuPermissions};
+use super::overlay::{use_scroll_lock, use_window_event};
+use super::route::MenuRoute;
+use super::storage;
+use super::Action;
+use crate::icon;
+
+/// How many recently run commands are remembered
+const RECENT_MAX: usize = 8;
+
+/// The score a match inside the label gets over one spread across the path
+const LABEL_BONUS: i32 = 10;
+
+/// How many matches are listed
+const RESULTS_MAX: usize = 50;
+
+const PATH_SEPARATOR: &str = " › ";
+
+/// A menu of the tree which can be run from the `CommandPalette`
+#[derive(Clone, PartialEq)]
+pub(crate) struct Command {
+    /// The labels from the root down to the menu
+    pub path: Vec<String>,
+    pub icon: Option<Element>,
+    pub shortcut: Option<String>,
+    pub disabled: bool,
+    pub check: Option<Check>,
+    pub route: Option<MenuRoute>,
+    pub action: Option<Action>,
+}
+
+impl Command {
+    /// The path shown for the command, e.g. `File › Export › PDF`, also its key in the recent list
+    fn title(&self) -> String {
+        self.path.join(PATH_SEPARATOR)
+    }
+
+    fn label(&self) -> &str {
+        self.path.last().map_or("", String::as_str)
+    }
+
+    fn run(&self) {
+        activate(&self.action, &self.check);
+        if let Some(route) = &self.route {
+            route.push();
+        }
+    }
+}
+
+/// A command matching the query, `hits` are the indices of the matched characters of its title
+#[derive(Clone, PartialEq)]
+struct Match {
+    command: Command,
+    hits: Vec<usize>,
+}
+
+/// Finds the characters of `query` in order inside `text`, ignoring case and spaces.
+/// Returns a score, higher for consecutive characters and word starts,
+/// with the indices of the matched characters.
+fn fuzzy_match(query: &str, text: &str) -> Option<(i32, Vec<usize>)> {
+    let text: Vec<char> = text.chars().collect();
+    let mut hits: Vec<usize> = vec![];
+    let mut score = 0;
+    let mut from = 0;
+    for q in query.chars().filter(|c| !c.is_whitespace()) {
+        let i = (from..text.len()).find(|&i| text[i].to_lowercase().eq(q.to_lowercase()))?;
+        score += 1;
+        if i > 0 && hits.last() == Some(&(i - 1)) {
+            score += 5;
+        }
+        if i == 0 || !text[i - 1].is_alphanumeric() {
+            score += 3;
+        }
+        // skipped characters make a looser match
+        score -= (i - from).min(3) as i32;
+        hits.push(i);
+        from = i + 1;
+    }
+    Some((score, hits))
+}
+
+/// The commands matching `query`, best first, the disabled ones last.
+/// Recent commands come first among equal matches, so with an empty query they lead.
+fn search(commands: Vec<Command>, query: &str, recent: &[String]) -> Vec<Match> {
+    let query = query.trim();
+    let mut matches: Vec<(i32, usize, Match)> = commands
+        .into_iter()
+        .filter_map(|command| {
+            let title = command.title();
+            let recent_rank = recent
+                .iter()
+                .position(|r| *r == title)
+                .unwrap_or(RECENT_MAX);
+            // a match inside the label beats one spread across the path
+            let label_start = title.chars().count() - command.label().chars().count();
+            let (score, hits) = if query.is_empty() {
+                (0, vec![])
+            } else if let Some((score, hits)) = fuzzy_match(query, command.label()) {
+                let hits = hits.into_iter().map(|i| i + label_start).collect();
+                (score + LABEL_BONUS, hits)
+            } else {
+                fuzzy_match(query, &title)?
+            };
+            Some((score, recent_rank, Match { command, hits }))
+        })
+        .collect();
+    // stable, so equal matches not run lately keep the order of the tree
+    matches.sort_by_key(|(score, rank, m)| (m.command.disabled, Reverse(*score), *rank));
+    matches
+        .into_iter()
+        .take(RESULTS_MAX)
+        .map(|(_, _, m)| m)
+        .collect()
+}
+
+#[derive(Clone, Copy)]
+struct PaletteState {
+    open: Signal<bool>,
+    query: Signal<String>,
+    /// The index of the highlighted match
+    active: Signal<usize>,
+    /// The titles of the last commands run, latest first
+    recent: Signal<Vec<String>>,
+    storage_key: Signal<String>,
+}
+
+impl PaletteState {
+    fn run(&mut self, command: &Command) {
+        if command.disabled {
+            return;
+        }
+        self.open.set(false);
+        let title = command.title();
+        let mut recent = self.recent.write();
+        recent.retain(|r| *r != title);
+        recent.insert(0, title);
+        recent.truncate(RECENT_MAX);
+        storage::save(&self.storage_key.peek(), &recent.join("\n"));
+        drop(recent);
+        command.run();
+    }
+}
+
+fn option_id(prefix: &str, index: usize) -> String {
+    format!("{prefix}_option_{index}")
+}
+
+/// Scrolls the option `id` into the list, if it is hidden
+fn scroll_to(id: &str) {
+    let element = window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.get_element_by_id(id));
+    if let Some(element) = element {
+        let options = ScrollIntoViewOptions::new();
+        options.set_block(ScrollLogicalPosition::Nearest);
+        element.scroll_into_view_with_scroll_into_view_options(&options);
+    }
+}
+
+/// A search box running any item of `menu_list`, nested ones included, e.g. for power users.
+///
+/// Ctrl+K (Cmd+K on macOS) opens and closes it, unless `shortcut` is false;
+/// `open` lets the app open it too, e.g. from a toolbar button.
+/// Each item is listed with the path leading to it, like `File › Export › PDF`.
+/// The query matches the characters of a path in order, so `fexp` finds `File › Export › PDF`.
+/// The arrows move through the matches and Enter runs one, calling its action,
+/// toggling its check or following its route like a click in the menu would.
+///
+/// Hidden menus are left out, disabled ones are listed but cannot be run.
+/// The commands run last are remembered in the local storage under `id_prefix`
+/// and come first among equal matches. Children loaded with `children_async` are not searched.
+///
+/// ```
+/// CommandPalette { menu_list: menu_list.clone() }
+/// ```
+#[component]
+pub fn CommandPalette(
+    menu_list: Vec<Menu>,
+    /// Opens and closes the palette from the app
+    open: Option<Signal<bool>>,
+    /// Whether Ctrl+K toggles the palette, read once when it is created
+    #[props(default = true)]
+    shortcut: bool,
+    #[props(into, default)] permissions: MenuPermissions,
+    #[props(into, default = "palette".to_string())] id_prefix: String,
+    #[props(into, default = "Search commands…".to_string())] placeholder: String,
+) -> Element {
+    const HEADER_CLASS: Asset = asset!("/assets/header.css");
+    let mut state = use_context_provider(|| {
+        let storage_key = format!("sam_ui.{id_prefix}.recent");
+        let recent = storage::load(&storage_key)
+            .map(|r| r.lines().take(RECENT_MAX).map(str::to_string).collect())
+            .unwrap_or_default();
+        PaletteState {
+            open: open.unwrap_or_else(|| Signal::new(false)),
+            query: Signal::new(String::new()),
+            active: Signal::new(0),
+            recent: Signal::new(recent),
+            storage_key: Signal::new(storage_key),
+        }
+    });
+    use_window_event("keydown", move |e| {
+        let Some(e) = e.dyn_ref::<KeyboardEvent>() else {
+            return;
+        };
+        if shortcut && (e.ctrl_key() || e.meta_key()) && e.key().eq_ignore_ascii_case("k") {
+            // the browser would focus its own search
+            e.prevent_default();
+            let open = !*state.open.peek();
+            state.open.set(open);
+        }
+    });
+    use_scroll_lock(state.open);
+
+    // the focus goes back where it was once the palette closes
+    let mut return_focus: Signal<Option<HtmlElement>> = use_signal(|| None);
+    let mut was_open = use_signal(|| false);
+    use_effect(move || {
+        let open = (state.open)();
+        if *was_open.peek() == open {
+            return;
+        }
+        was_open.set(open);
+        if open {
+            state.query.set(String::new());
+            state.active.set(0);
+            let active = window()
+                .and_then(|w| w.document())
+                .and_then(|d| d.active_element())
+                .and_then(|a| a.dyn_into::<HtmlElement>().ok());
+            return_focus.set(active);
+        } else if let Some(element) = return_focus.take() {
+            _ = element.focus();
+        }
+    });
+
+    let id_clone_1 = id_prefix.clone();
+    use_effect(move || {
+        let active = (state.active)();
+        if (state.open)() {
+            scroll_to(&option_id(&id_clone_1, active));
+        }
+    });
+
+    // only read while open, so the flags of closed menus do not render the palette again
+    let results = if (state.open)() {
+        let mut menu_list = menu_list;
+        if let Some(held) = permissions.get() {
+            menu_list = permitted(menu_list, &held);
+        }
+        search(
+            commands(&menu_list, &[], false),
+            &(state.query)(),
+            &(state.recent)(),
+        )
+    } else {
+        vec![]
+    };
+    let active = (state.active)().min(results.len().saturating_sub(1));
+    let list_id = format!("{id_prefix}_list");
+    let active_option = (!results.is_empty()).then(|| option_id(&id_prefix, active));
+
+    let key_handler = {
+        let results = results.clone();
+        move |e: Event<KeyboardData>| {
+            let count = results.len();
+            match e.key() {
+                Key::ArrowDown if count > 0 => state.active.set((active + 1) % count),
+                Key::ArrowUp if count > 0 => state.active.set((active + count - 1) % count),
+                Key::Enter => {
+                    if let Some(result) = results.get(active) {
+                        state.run(&result.command);
+                    }
+                }
+                Key::Escape => state.open.set(false),
+                // the input is the only stop of the dialog
+                Key::Tab => {}
+                _ => return,
+            }
+            e.prevent_default();
+        }
+    };
+
+    rsx! {
+        document::Stylesheet { href: "{HEADER_CLASS}" }
+        if (state.open)() {
+            div {
+                class: "palette_backdrop",
+                z_index: 20,
+                aria_hidden: "true",
+                onclick: move |_| state.open.set(false),
+            }
+            div {
+                class: "command_palette",
+                z_index: 21,
+                role: "dialog",
+                aria_modal: "true",
+                aria_label: "Command palette",
+                div { class: "palette_search",
+                    {icon!(LdSearch, 18, "none", "currentColor")}
+                    input {
+                        class: "palette_input",
+                        r#type: "text",
+                        role: "combobox",
+                        autocomplete: "off",
+                        spellcheck: "false",
+                        aria_label: "Search commands",
+                        aria_autocomplete: "list",
+                        aria_expanded: "true",
+                        aria_controls: "{list_id}",
+                        aria_activedescendant: active_option,
+                        placeholder,
+                        value: (state.query)(),
+                        onmounted: move |elem: Event<MountedData>| async move {
+                            _ = elem.data().set_focus(true).await;
+                        },
+                        oninput: move |e: Event<FormData>| {
+                            state.query.set(e.value());
+                            state.active.set(0);
+                        },
+                        onkeydown: key_handler,
+                    }
+                }
+                if results.is_empty() {
+                    div { class: "palette_empty", role: "status", "No matching commands" }
+                } else {
+                    ul {
+                        class: "palette_list",
+                        id: "{list_id}",
+                        role: "listbox",
+                        aria_label: "Commands",
+                        for (index, result) in results.into_iter().enumerate() {
+                            PaletteOption {
+                                id: option_id(&id_prefix, index),
+                                index,
+                                result,
+                                is_active: index == active,
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// `chars` split into runs of characters which are all hits or all misses,
+/// `offset` is the index of the first one
+fn runs(chars: &[char], offset: usize, hits: &[usize]) -> Vec<(String, bool)> {
+    let mut runs: Vec<(String, bool)> = vec![];
+    for (i, c) in chars.iter().enumerate() {
+        let hit = hits.contains(&(i + offset));
+        match runs.last_mut() {
+            Some((run, run_hit)) if *run_hit == hit => run.push(*c),
+            _ => runs.push((c.to_string(), hit)),
+        }
+    }
+    runs
+}
+
+/// `chars` with the characters at `hits` marked, `offset` is the index of the first one
+fn highlight(chars: &[char], offset: usize, hits: &[usize]) -> Element {
+    rsx! {
+        for (run, hit) in runs(chars, offset, hits) {
+            if hit {
+                mark { class: "palette_hit", "{run}" }
+            } else {
+                "{run}"
+            }
+        }
+    }
+}
+
+#[component]
+fn PaletteOption(id: String, index: usize, result: Match, is_active: bool) -> Element {
+    let mut state = use_context::<PaletteState>();
+    let Match { command, hits } = result;
+    let title: Vec<char> = command.title().chars().collect();
+    let label_start = title.len() - command.label().chars().count();
+    let (ancestors, label) = title.split_at(label_start);
+    let is_checked = command.check.as_ref().is_some_and(|c| c.is_checked());
+    let disabled = command.disabled;
+    let icon = command.icon.clone();
+    let shortcut = command.shortcut.clone();
+    rsx! {
+        li {
+            class: "palette_option",
+            class: if is_active { "active" },
+            class: if disabled { "disabled" },
+            id,
+            role: "option",
+            aria_selected: "{is_active}",
+            aria_disabled: disabled.then_some("true"),
+            aria_checked: command.check.as_ref().map(|_| is_checked.to_string()),
+            // keeps the focus in the input
+            onmousedown: move |e: Event<MouseData>| e.prevent_default(),
+            onpointermove: move |_| {
+                if *state.active.peek() != index {
+                    state.active.set(index);
+                }
+            },
+            onclick: move |_| state.run(&command),
+            span { class: "palette_icon", aria_hidden: "true",
+                if is_checked {
+                    {icon!(LdCheck, 16, "none", "currentColor")}
+                } else if let Some(icon) = icon {
+                    {icon}
+                }
+            }
+            span { class: "palette_text",
+                if !ancestors.is_empty() {
+                    span { class: "palette_path", {highlight(ancestors, 0, &hits)} }
+                }
+                span { class: "palette_label", {highlight(label, label_start, &hits)} }
+            }
+            if let Some(shortcut) = shortcut {
+                kbd { class: "palette_shortcut", "{shortcut}" }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(path: &[&str], disabled: bool) -> Command {
+        Command {
+            path: path.iter().map(|p| p.to_string()).collect(),
+            icon: None,
+            shortcut: None,
+            disabled,
+            check: None,
+            route: None,
+            action: None,
+        }
+    }
+
+    fn titles(matches: &[Match]) -> Vec<String> {
+        matches.iter().map(|m| m.command.title()).collect()
+    }
+
+    #[test]
+    fn fuzzy_match_finds_characters_in_order() {
+        let (_, hits) = fuzzy_match("sva", "Save as").unwrap();
+        assert_eq!(hits, vec![0, 2, 5]);
+        assert!(fuzzy_match("as s", "Save").is_none());
+    }
+
+    #[test]
+    fn fuzzy_match_prefers_consecutive_characters() {
+        let (tight, _) = fuzzy_match("exp", "Export").unwrap();
+        let (loose, _) = fuzzy_match("exp", "Extra options").unwrap();
+        assert!(tight > loose);
+    }
+
+    #[test]
+    fn fuzzy_match_indexes_characters_of_multibyte_text() {
+        let (_, hits) = fuzzy_match("ÖÄ", "Größe ändern").unwrap();
+        assert_eq!(hits, vec![2, 6]);
+    }
+
+    #[test]
+    fn search_offsets_label_hits_into_the_title() {
+        let commands = vec![command(&["Édition", "Größe ändern"], false)];
+        let results = search(commands, "öä", &[]);
+        assert_eq!(results[0].hits, vec![12, 16]);
+    }
+
+    #[test]
+    fn search_falls_back_to_the_path() {
+        let results = search(
+            vec![
+                command(&["File", "Export", "PDF"], false),
+                command(&["File", "Open"], false),
+            ],
+            "fexp",
+            &[],
+        );
+        assert_eq!(titles(&results), vec!["File › Export › PDF"]);
+        assert_eq!(results[0].hits, vec![0, 3, 8, 9]);
+    }
+
+    #[test]
+    fn search_lists_disabled_commands_last() {
+        let results = search(
+            vec![command(&["Save"], true), command(&["Save as"], false)],
+            "save",
+            &[],
+        );
+        assert_eq!(titles(&results), vec!["Save as", "Save"]);
+    }
+
+    #[test]
+    fn search_ranks_recent_commands_among_equal_matches() {
+        let commands = vec![
+            command(&["Open"], false),
+            command(&["Save"], false),
+            command(&["Shop"], false),
+        ];
+        let recent = vec!["Shop".to_string(), "Save".to_string()];
+        let results = search(commands.clone(), "", &recent);
+        assert_eq!(titles(&results), vec!["Shop", "Save", "Open"]);
+        // a better match beats a recent one
+        let results = search(commands, "o", &recent);
+        assert_eq!(titles(&results), vec!["Open", "Shop"]);
+    }
+
+    #[test]
+    fn runs_split_hits_from_misses() {
+        let chars: Vec<char> = "Größe".chars().collect();
+        assert_eq!(
+            runs(&chars, 10, &[10, 12, 13]),
+            vec![
+                ("G".to_string(), true),
+                ("r".to_string(), false),
+                ("öß".to_string(), true),
+                ("e".to_string(), false),
+            ]
+        );
+        assert_eq!(runs(&chars, 0, &[]), vec![("Größe".to_string(), false)]);
+    }
+}
@@ -0,0 +1,453 @@
+use dioxus::prelude::*;
+use std::cell::Cell;
+use std::rc::Rc;
+use wasm_bindgen::{prelude::Closure, JsCast, JsValue};
+use web_sys::{window, Event, ResizeObserver};
+
+/// Where a flyout opens relative to the element it belongs to.
+/// It moves to the other side when it does not fit.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Placement {
+    /// Under the anchor with the left edges aligned, used by the root dropdowns
+    #[default]
+    Below,
+    /// Under the anchor with the right edges aligned
+    BelowEnd,
+    /// Over the anchor with the left edges aligned
+    Above,
+    /// Over the anchor with the right edges aligned
+    AboveEnd,
+    /// Right of the anchor, used by the nested menus
+    Beside,
+    /// Left of the anchor
+    Before,
+}
+
+/// A rectangle in viewport coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl From<web_sys::DomRect> for Rect {
+    fn from(rect: web_sys::DomRect) -> Self {
+        Self {
+            x: rect.x(),
+            y: rect.y(),
+            width: rect.width(),
+            height: rect.height(),
+        }
+    }
+}
+
+/// The offset of a flyout from the top left corner of its anchor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct FlyoutPosition {
+    pub left: f64,
+    pub top: f64,
+}
+
+/// The offset of a full-width panel from its anchor, with the width it spans
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct PanelPosition {
+    pub left: f64,
+    pub top: f64,
+    pub width: f64,
+}
+
+/// Places a flyout of `size` (width, height) next to `anchor`, flipping it to the other side
+/// when it does not fit and clamping it inside `viewport` (width, height).
+pub(crate) fn place(
+    placement: Placement,
+    anchor: Rect,
+    size: (f64, f64),
+    viewport: (f64, f64),
+) -> FlyoutPosition {
+    let (width, height) = size;
+    let (viewport_width, viewport_height) = viewport;
+    let right_space = viewport_width - anchor.x - anchor.width;
+    let bottom_space = viewport_height - anchor.y - anchor.height;
+
+    let (left, top) = match placement {
+        Placement::Below | Placement::BelowEnd | Placement::Above | Placement::AboveEnd => {
+            // align the chosen edges, or the other ones when overflowing
+            let left = match placement {
+                Placement::Below | Placement::Above if anchor.x + width > viewport_width => {
+                    anchor.width - width
+                }
+                Placement::Below | Placement::Above => 0.0,
+                _ if anchor.x + anchor.width < width => 0.0,
+                _ => anchor.width - width,
+            };
+            let below = match placement {
+                Placement::Below | Placement::BelowEnd => {
+                    height <= bottom_space || anchor.y < height
+                }
+                _ => anchor.y < height && height <= bottom_space,
+            };
+            let top = if below { anchor.height } else { -height };
+            (left, top)
+        }
+        Placement::Beside | Placement::Before => {
+            let right = match placement {
+                Placement::Beside => width <= right_space || anchor.x <= right_space,
+                _ => anchor.x < width && anchor.x < right_space,
+            };
+            let left = if right { anchor.width } else { -width };
+            // align the top edges, or the bottom edges when overflowing
+            let top = if anchor.y + height > viewport_height && anchor.y + anchor.height >= height {
+                anchor.height - height
+            } else {
+                0.0
+            };
+            (left, top)
+        }
+    };
+
+    // keep the whole flyout on screen
+    let x = (anchor.x + left).min(viewport_width - width).max(0.0);
+    let y = (anchor.y + top).min(viewport_height - height).max(0.0);
+    FlyoutPosition {
+        left: x - anchor.x,
+        top: y - anchor.y,
+    }
+}
+
+/// Places `flyout` at `point` like a context menu: below and right of it, flipped and
+/// clamped to stay on screen. Unlike `place`, the position is in viewport coordinates.
+pub(crate) fn place_at(point: (f64, f64), flyout: &web_sys::Element) -> Option<FlyoutPosition> {
+    let anchor = Rect {
+        x: point.0,
+        y: point.1,
+        ..Rect::default()
+    };
+    let size = flyout.get_bounding_client_rect();
+    let offset = place(
+        Placement::Below,
+        anchor,
+        (size.width(), size.height()),
+        viewport_size()?,
+    );
+    Some(FlyoutPosition {
+        left: point.0 + offset.left,
+        top: point.1 + offset.top,
+    })
+}
+
+fn viewport_size() -> Option<(f64, f64)> {
+    let win = window()?;
+    let width = win.inner_width().ok()?.as_f64()?;
+    let height = win.inner_height().ok()?.as_f64()?;
+    Some((width, height))
+}
+
+/// Calls `callback` with every window resize and scroll (of the page or any scrolling parent).
+pub(crate) fn use_window_resize_and_scroll(callback: impl FnMut(&Event) + 'static) {
+    let active = use_signal(|| true);
+    use_window_resize_and_scroll_while(active, callback);
+}
+
+/// Like `use_window_resize_and_scroll`, only listening while `active` is true.
+pub(crate) fn use_window_resize_and_scroll_while(
+    active: Signal<bool>,
+    mut callback: impl FnMut(&Event) + 'static,
+) {
+    let closure = use_hook(|| {
+        Rc::new(Closure::wrap(
+            Box::new(move |event: Event| callback(&event)) as Box<dyn FnMut(Event)>,
+        ))
+    });
+    let listening = use_hook(|| Rc::new(Cell::new(false)));
+
+    let listen = move |listen: bool| {
+        let Some(win) = window() else {
+            return;
+        };
+        if listening.get() == listen {
+            return;
+        }
+        for event in ["resize", "scroll"] {
+            let callback = closure.as_ref().as_ref().unchecked_ref();
+            if listen {
+                win.add_event_listener_with_callback_and_bool(event, callback, true)
+                    .ok();
+            } else {
+                win.remove_event_listener_with_callback_and_bool(event, callback, true)
+                    .ok();
+            }
+        }
+        listening.set(listen);
+    };
+
+    use_effect({
+        let listen = listen.clone();
+        move || listen(active())
+    });
+    use_drop(move || listen(false));
+}
+
+/// Calls `callback` whenever the element in `target` changes size, e.g. when its content loads.
+pub(crate) fn use_resize_observer(
+    target: Signal<Option<web_sys::Element>>,
+    mut callback: impl FnMut() + 'static,
+) {
+    let observer = use_hook(|| {
+        let closure =
+            Closure::wrap(Box::new(move |_: JsValue| callback()) as Box<dyn FnMut(JsValue)>);
+        let observer = ResizeObserver::new(closure.as_ref().unchecked_ref()).ok()?;
+        Some(Rc::new((observer, closure)))
+    });
+
+    use_effect({
+        let observer = observer.clone();
+        move || {
+            let Some(observer) = &observer else {
+                return;
+            };
+            observer.0.disconnect();
+            if let Some(target) = target.read().as_ref() {
+                observer.0.observe(target);
+            }
+        }
+    });
+    use_drop(move || {
+        if let Some(observer) = observer {
+            observer.0.disconnect();
+        }
+    });
+}
+
+/// Positions a flyout against `anchor` while `open`, measuring again on resize and scroll
+/// and when the size of the flyout changes.
+///
+/// Returns the signal the flyout should store its element in from `onmounted`
+/// and its position, which stays `None` until both elements are measured.
+pub(crate) fn use_flyout(
+    anchor: Signal<Option<web_sys::Element>>,
+    open: Signal<bool>,
+    placement: Placement,
+) -> (
+    Signal<Option<web_sys::Element>>,
+    Signal<Option<FlyoutPosition>>,
+) {
+    let mut flyout: Signal<Option<web_sys::Element>> = use_signal(|| None);
+    let mut position: Signal<Option<FlyoutPosition>> = use_signal(|| None);
+
+    let mut update = move || {
+        let (Some(anchor), Some(flyout), Some(viewport)) = (
+            anchor.peek().clone(),
+            flyout.peek().clone(),
+            viewport_size(),
+        ) else {
+            return;
+        };
+        let size = flyout.get_bounding_client_rect();
+        let new_position = place(
+            placement,
+            anchor.get_bounding_client_rect().into(),
+            (size.width(), size.height()),
+            viewport,
+        );
+        if *position.peek() != Some(new_position) {
+            position.set(Some(new_position));
+        }
+    };
+
+    // the element goes away with the flyout, the next one is measured once mounted
+    use_effect(move || {
+        if !open() && flyout.peek().is_some() {
+            flyout.set(None);
+            position.set(None);
+        }
+    });
+    use_effect(move || {
+        if flyout.read().is_some() {
+            update();
+        }
+    });
+    use_resize_observer(flyout, update);
+    use_window_resize_and_scroll_while(open, move |_| update());
+
+    (flyout, position)
+}
+
+/// Spans a panel across `container` right under it, e.g. a mega menu under the `MenuBar`,
+/// measuring when `open` turns true and on resize and scroll. Without a container it spans the viewport.
+///
+/// Like `use_flyout`, the position is relative to `anchor`, the element the panel belongs to.
+pub(crate) fn use_full_width(
+    anchor: Signal<Option<web_sys::Element>>,
+    container: Signal<Option<web_sys::Element>>,
+    open: Signal<bool>,
+) -> Signal<Option<PanelPosition>> {
+    let mut position: Signal<Option<PanelPosition>> = use_signal(|| None);
+
+    let mut update = move || {
+        let Some(anchor) = anchor.peek().clone() else {
+            return;
+        };
+        let anchor: Rect = anchor.get_bounding_client_rect().into();
+        let span = match container.peek().as_ref() {
+            Some(container) => container.get_bounding_client_rect().into(),
+            None => {
+                let Some((width, _)) = viewport_size() else {
+                    return;
+                };
+                Rect {
+                    y: anchor.y,
+                    width,
+                    height: anchor.height,
+                    ..Rect::default()
+                }
+            }
+        };
+        let new_position = PanelPosition {
+            left: span.x - anchor.x,
+            top: span.y + span.height - anchor.y,
+            width: span.width,
+        };
+        if *position.peek() != Some(new_position) {
+            position.set(Some(new_position));
+        }
+    };
+
+    use_effect(move || {
+        if open() && anchor.read().is_some() {
+            update();
+        }
+    });
+    use_window_resize_and_scroll_while(open, move |_| update());
+
+    position
+}
+
+/// The inline style of a full-width panel, hidden until it has been measured
+pub(crate) fn panel_style(position: Option<PanelPosition>) -> String {
+    match position {
+        Some(PanelPosition { left, top, width }) => {
+            format!("left: {left}px; top: {top}px; width: {width}px;")
+        }
+        None => "visibility: hidden;".to_string(),
+    }
+}
+
+/// The inline style of a flyout, hidden until it has been measured
+pub(crate) fn flyout_style(position: Option<FlyoutPosition>) -> String {
+    match position {
+        Some(FlyoutPosition { left, top }) => format!("left: {left}px; top: {top}px;"),
+        None => "visibility: hidden;".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VIEWPORT: (f64, f64) = (1000.0, 800.0);
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> Rect {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn at(left: f64, top: f64) -> FlyoutPosition {
+        FlyoutPosition { left, top }
+    }
+
+    #[test]
+    fn below_opens_under_the_anchor() {
+        let anchor = rect(100.0, 10.0, 80.0, 30.0);
+        let position = place(Placement::Below, anchor, (200.0, 300.0), VIEWPORT);
+        assert_eq!(position, at(0.0, 30.0));
+    }
+
+    #[test]
+    fn below_flips_above_at_the_bottom_edge() {
+        let anchor = rect(100.0, 700.0, 80.0, 30.0);
+        let position = place(Placement::Below, anchor, (200.0, 300.0), VIEWPORT);
+        assert_eq!(position, at(0.0, -300.0));
+    }
+
+    #[test]
+    fn below_aligns_the_right_edges_at_the_right_edge() {
+        let anchor = rect(900.0, 10.0, 80.0, 30.0);
+        let position = place(Placement::Below, anchor, (200.0, 300.0), VIEWPORT);
+        assert_eq!(position, at(-120.0, 30.0));
+    }
+
+    #[test]
+    fn below_end_aligns_the_left_edges_at_the_left_edge() {
+        let anchor = rect(10.0, 10.0, 50.0, 30.0);
+        let position = place(Placement::BelowEnd, anchor, (200.0, 300.0), VIEWPORT);
+        assert_eq!(position, at(0.0, 30.0));
+    }
+
+    #[test]
+    fn above_flips_below_at_the_top_edge() {
+        let anchor = rect(100.0, 50.0, 80.0, 30.0);
+        let position = place(Placement::Above, anchor, (200.0, 300.0), VIEWPORT);
+        assert_eq!(position, at(0.0, 30.0));
+    }
+
+    #[test]
+    fn beside_opens_right_of_the_anchor() {
+        let anchor = rect(100.0, 100.0, 150.0, 30.0);
+        let position = place(Placement::Beside, anchor, (200.0, 300.0), VIEWPORT);
+        assert_eq!(position, at(150.0, 0.0));
+    }
+
+    #[test]
+    fn beside_flips_left_at_the_right_edge() {
+        let anchor = rect(700.0, 100.0, 150.0, 30.0);
+        let position = place(Placement::Beside, anchor, (200.0, 300.0), VIEWPORT);
+        assert_eq!(position, at(-200.0, 0.0));
+    }
+
+    #[test]
+    fn beside_aligns_the_bottom_edges_at_the_bottom_edge() {
+        let anchor = rect(100.0, 600.0, 150.0, 30.0);
+        let position = place(Placement::Beside, anchor, (200.0, 300.0), VIEWPORT);
+        assert_eq!(position, at(150.0, -270.0));
+    }
+
+    #[test]
+    fn before_opens_left_and_flips_at_the_left_edge() {
+        let anchor = rect(500.0, 100.0, 150.0, 30.0);
+        let position = place(Placement::Before, anchor, (200.0, 300.0), VIEWPORT);
+        assert_eq!(position, at(-200.0, 0.0));
+        let anchor = rect(50.0, 100.0, 150.0, 30.0);
+        let position = place(Placement::Before, anchor, (200.0, 300.0), VIEWPORT);
+        assert_eq!(position, at(150.0, 0.0));
+    }
+
+    #[test]
+    fn clamps_at_the_left_edge() {
+        let anchor = rect(900.0, 10.0, 20.0, 30.0);
+        let position = place(Placement::Below, anchor, (960.0, 300.0), VIEWPORT);
+        assert_eq!(position, at(-900.0, 30.0));
+    }
+
+    #[test]
+    fn clamps_at_the_right_edge() {
+        let anchor = rect(100.0, 0.0, 100.0, 30.0);
+        let position = place(Placement::Beside, anchor, (150.0, 100.0), (300.0, 800.0));
+        assert_eq!(position, at(50.0, 0.0));
+    }
+
+    #[test]
+    fn clamps_at_the_top_and_bottom_edges() {
+        let anchor = rect(0.0, 150.0, 50.0, 30.0);
+        let viewport = (1000.0, 400.0);
+        let position = place(Placement::Above, anchor, (200.0, 300.0), viewport);
+        assert_eq!(position, at(0.0, -150.0));
+        let position = place(Placement::Below, anchor, (200.0, 300.0), viewport);
+        assert_eq!(position, at(0.0, -50.0));
+    }
+}
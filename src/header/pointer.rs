@@ -0,0 +1,66 @@
+use dioxus::prelude::*;
+
+use super::hover::{use_hover_timer, HoverTimer};
+
+/// How long a touch has to be held to expand a nested menu, in ms
+const LONG_PRESS: u64 = 500;
+
+/// The kind of pointer which pressed last: taps open before they activate.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) enum PointerKind {
+    #[default]
+    Mouse,
+    /// A finger or a pen
+    Touch,
+}
+
+impl PointerKind {
+    pub fn of(pointer_type: &str) -> Self {
+        match pointer_type {
+            "touch" | "pen" => PointerKind::Touch,
+            _ => PointerKind::Mouse,
+        }
+    }
+}
+
+/// Whether a pointer event comes from a mouse, only those drive the hover behaviour
+pub(crate) fn is_mouse(e: &Event<PointerData>) -> bool {
+    PointerKind::of(&e.pointer_type()) == PointerKind::Mouse
+}
+
+/// Runs an action when a touch is held on an element and swallows the click following it.
+#[derive(Clone, Copy)]
+pub(crate) struct LongPress {
+    timer: HoverTimer,
+    fired: Signal<bool>,
+}
+
+impl LongPress {
+    pub fn start(&mut self, f: impl FnOnce() + 'static) {
+        let mut fired = self.fired;
+        fired.set(false);
+        self.timer.start(LONG_PRESS, None, move || {
+            fired.set(true);
+            f();
+        });
+    }
+
+    pub fn cancel(&mut self) {
+        self.timer.cancel();
+    }
+
+    /// Whether the click being handled ends a long press
+    pub fn take_fired(&mut self) -> bool {
+        let fired = *self.fired.peek();
+        if fired {
+            self.fired.set(false);
+        }
+        fired
+    }
+}
+
+pub(crate) fn use_long_press() -> LongPress {
+    let timer = use_hover_timer();
+    let fired = use_signal(|| false);
+    LongPress { timer, fired }
+}
@@ -0,0 +1,140 @@
+use dioxus::prelude::*;
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::{prelude::Closure, JsCast};
+use web_sys::{window, Event, MediaQueryList};
+
+use super::hover::OpenOn;
+use super::menu::{Menu, MenuPermissions};
+use super::{DrawerSide, MenuBar, MenuList};
+
+/// Removes its listener from the list when dropped
+struct MediaListener {
+    list: MediaQueryList,
+    closure: Closure<dyn FnMut(Event)>,
+}
+
+impl Drop for MediaListener {
+    fn drop(&mut self) {
+        self.list
+            .remove_event_listener_with_callback("change", self.closure.as_ref().unchecked_ref())
+            .ok();
+    }
+}
+
+fn listen(query: &str, mut matches: Signal<bool>) -> Option<MediaListener> {
+    let list = window()?.match_media(query).ok().flatten()?;
+    let list_clone = list.clone();
+    let closure = Closure::wrap(Box::new(move |_: Event| {
+        matches.set(list_clone.matches());
+    }) as Box<dyn FnMut(Event)>);
+    list.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())
+        .ok()?;
+    if *matches.peek() != list.matches() {
+        matches.set(list.matches());
+    }
+    Some(MediaListener { list, closure })
+}
+
+/// Follows a CSS media query through `matchMedia`, e.g. `use_media_query("(min-width: 768px)")`.
+///
+/// It is `false` until the component is mounted, so the server and the first render
+/// of the client agree, and it follows changes of `query`.
+pub fn use_media_query(query: &str) -> ReadOnlySignal<bool> {
+    let matches = use_signal(|| false);
+    let listener: Rc<RefCell<Option<MediaListener>>> = use_hook(|| Rc::new(RefCell::new(None)));
+
+    let query = query.to_string();
+    let listener_clone = listener.clone();
+    use_effect(use_reactive((&query,), move |(query,)| {
+        // the listener of the previous query goes away first
+        listener_clone.borrow_mut().take();
+        *listener_clone.borrow_mut() = listen(&query, matches);
+    }));
+    use_drop(move || {
+        listener.borrow_mut().take();
+    });
+
+    matches.into()
+}
+
+/// A header rendering the `MenuBar` on wide screens and the burger `MenuList` below `breakpoint`.
+///
+/// Both modes are built from the same `menu_list`, so their actions are shared.
+/// The `MenuBar` shows once the page is mounted, the server renders the `MenuList`.
+///
+/// ```
+/// Header {
+///     menu_list,
+///     breakpoint: 900,
+///     logo: rsx! { img { src: LOGO } },
+///     trailing: rsx! { button { "Sign in" } },
+/// }
+/// ```
+#[component]
+pub fn Header(
+    menu_list: Vec<Menu>,
+    /// The minimum width in px showing the `MenuBar`
+    #[props(default = 768)]
+    breakpoint: u32,
+    logo: Option<Element>,
+    trailing: Option<Element>,
+    /// Passed to the `MenuBar` and `MenuList`
+    #[props(into, default)]
+    permissions: MenuPermissions,
+    /// Passed to the `MenuList`
+    #[props(default)]
+    side: DrawerSide,
+    /// Passed to the `MenuList`
+    #[props(default)]
+    accordion: bool,
+    /// Passed to the `MenuBar`
+    #[props(default)]
+    open_on: OpenOn,
+    /// Passed to the `MenuBar`
+    #[props(default = 100)]
+    open_delay: u64,
+    /// Passed to the `MenuBar`
+    #[props(default = 300)]
+    close_delay: u64,
+    /// Passed to the `MenuBar`
+    on_open_change: Option<EventHandler<Option<String>>>,
+    /// Passed to the `MenuList` as its `on_open_change`
+    on_drawer_change: Option<EventHandler<bool>>,
+    /// Passed to the `MenuBar` and `MenuList`, only one of them is rendered at a time
+    #[props(into, default = "menu".to_string())]
+    id_prefix: String,
+) -> Element {
+    let wide = use_media_query(&format!("(min-width: {breakpoint}px)"));
+    rsx! {
+        header { class: "header",
+            if let Some(logo) = logo {
+                div { class: "header_logo", {logo} }
+            }
+            div { class: "header_menu",
+                if wide() {
+                    MenuBar {
+                        menu_list,
+                        permissions,
+                        open_on,
+                        open_delay,
+                        close_delay,
+                        on_open_change,
+                        id_prefix,
+                    }
+                } else {
+                    MenuList {
+                        menu_list,
+                        permissions,
+                        side,
+                        accordion,
+                        on_open_change: on_drawer_change,
+                        id_prefix,
+                    }
+                }
+            }
+            if let Some(trailing) = trailing {
+                div { class: "header_trailing", {trailing} }
+            }
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use dioxus::prelude::*;
+#[cfg(feature = "router")]
+use dioxus::router::prelude::{navigator, router, Link, Routable};
+#[cfg(feature = "router")]
+use std::rc::Rc;
+
+/// The route a menu links to, only built with the `router` feature.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "router"), allow(dead_code))]
+pub(crate) struct MenuRoute {
+    pub path: String,
+    #[cfg(feature = "router")]
+    current: Rc<dyn Fn() -> String>,
+    #[cfg(feature = "router")]
+    push: Rc<dyn Fn()>,
+    #[cfg(feature = "router")]
+    link: Rc<dyn Fn(Element) -> Element>,
+}
+
+#[cfg(feature = "router")]
+impl MenuRoute {
+    pub fn new<R: Routable>(route: R) -> Self {
+        let path = route.to_string();
+        let link_route = route.clone();
+        Self {
+            path,
+            current: Rc::new(|| router().current::<R>().to_string()),
+            push: Rc::new(move || {
+                navigator().push(route.clone());
+            }),
+            link: Rc::new(move |content| {
+                rsx! {
+                    // plain clicks are handled by the item, see `opens_elsewhere`
+                    Link {
+                        class: "menu_link",
+                        to: link_route.clone(),
+                        role: "none",
+                        tabindex: -1,
+                        onclick: |_| {},
+                        onclick_only: true,
+                        {content}
+                    }
+                }
+            }),
+        }
+    }
+
+    /// Whether the app is on this route, subscribing the current component to route changes
+    pub fn is_active(&self) -> bool {
+        (self.current)() == self.path
+    }
+
+    pub fn push(&self) {
+        (self.push)()
+    }
+}
+
+#[cfg(not(feature = "router"))]
+impl MenuRoute {
+    pub fn is_active(&self) -> bool {
+        false
+    }
+
+    pub fn push(&self) {}
+}
+
+impl std::fmt::Debug for MenuRoute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MenuRoute({:?})", self.path)
+    }
+}
+
+impl PartialEq for MenuRoute {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path
+    }
+}
+
+/// Wraps `content` in a real link so the route can be opened in a new tab.
+/// Disabled menus get no link.
+#[cfg(feature = "router")]
+pub(crate) fn route_link(route: &Option<MenuRoute>, disabled: bool, content: Element) -> Element {
+    match route {
+        Some(route) if !disabled => (route.link)(content),
+        _ => content,
+    }
+}
+
+#[cfg(not(feature = "router"))]
+pub(crate) fn route_link(_route: &Option<MenuRoute>, _disabled: bool, content: Element) -> Element {
+    content
+}
+
+/// Whether a click is left to the link of the route, e.g. ctrl + click opening a new tab
+#[cfg(feature = "router")]
+pub(crate) fn opens_elsewhere(route: &Option<MenuRoute>, e: &MouseData) -> bool {
+    route.is_some() && !e.modifiers().is_empty()
+}
+
+#[cfg(not(feature = "router"))]
+pub(crate) fn opens_elsewhere(_route: &Option<MenuRoute>, _e: &MouseData) -> bool {
+    false
+}
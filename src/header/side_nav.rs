@@ -0,0 +1,346 @@
+use dioxus::prelude::*;
+
+use super::check::Check;
+use super::label::MenuLabel;
+use super::lazy::{use_children, ChildrenCache, ChildrenLoader};
+use super::menu::{
+    activate, menu_list_id, permitted, route_state, use_panel_warning, with_ids, Menu, MenuContent,
+    MenuDecor, MenuFlag, MenuPermissions,
+};
+use super::nav;
+use super::placement::{flyout_style, use_flyout, Placement};
+use super::route::{opens_elsewhere, route_link, MenuRoute};
+use super::storage;
+use super::Action;
+use crate::icon;
+
+/// The expanded sections and the rail mode of a `SideNav`
+#[derive(Clone, Copy)]
+pub(crate) struct SideNavState {
+    pub collapsed: Signal<bool>,
+    /// The ids of the open sections
+    pub expanded: Signal<Vec<String>>,
+    /// The local storage key the state is saved under
+    storage_key: Signal<Option<String>>,
+}
+
+impl SideNavState {
+    pub fn is_expanded(&self, id: &str) -> bool {
+        self.expanded.read().iter().any(|e| e == id)
+    }
+
+    pub fn toggle(&mut self, id: &str) {
+        if self.expanded.peek().iter().any(|e| e == id) {
+            self.expanded.write().retain(|e| e != id);
+        } else {
+            self.expanded.write().push(id.to_string());
+        }
+        self.save();
+    }
+
+    pub fn set_collapsed(&mut self, collapsed: bool) {
+        self.collapsed.set(collapsed);
+        self.save();
+    }
+
+    fn save(&self) {
+        let Some(key) = self.storage_key.peek().clone() else {
+            return;
+        };
+        storage::save(
+            &format!("{key}.collapsed"),
+            &self.collapsed.peek().to_string(),
+        );
+        storage::save(&format!("{key}.expanded"), &self.expanded.peek().join(","));
+    }
+}
+
+/// The rail mode and expanded sections saved under `key`
+fn load(key: &str) -> Option<(bool, Vec<String>)> {
+    let collapsed = storage::load(&format!("{key}.collapsed"))?;
+    let expanded = storage::load(&format!("{key}.expanded"))?;
+    let expanded = expanded
+        .split(',')
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .collect();
+    Some((collapsed == "true", expanded))
+}
+
+/// Marks the menus rendered inside a flyout of the rail, they show their labels
+#[derive(Clone, Copy)]
+struct InFlyout;
+
+/// A vertical navigation, e.g. the left sidebar of a dashboard.
+///
+/// Menus with children are sections expanding below themselves. The toggle at the top
+/// collapses the whole nav into a rail of icons, where hovering or focusing a menu
+/// shows its label, and the children of a section, in a flyout.
+/// The menu of the current route and its sections are highlighted, see `Menu::to`.
+///
+/// With `persist`, the rail mode and the expanded sections are kept in the local storage
+/// under `id_prefix`, so they survive reloads. Give each `SideNav` its own `id_prefix`,
+/// and its sections a `key` so they stay expanded when the menus change.
+/// The saved state is restored once mounted, the first render matches the server one.
+///
+/// ```
+/// SideNav {
+///     menu_list: vec![
+///         Menu::new("Dashboard").icon(icon!(LdHouse, 18)).to(Route::Home {}),
+///         Menu::new("Settings").icon(icon!(LdSettings, 18)).children(settings),
+///     ],
+/// }
+/// ```
+#[component]
+pub fn SideNav(
+    menu_list: Vec<Menu>,
+    #[props(into, default = "side".to_string())] id_prefix: String,
+    #[props(into, default)] permissions: MenuPermissions,
+    #[props(default = true)] persist: bool,
+    /// Called with `true` when the nav collapses into a rail, `false` when it expands again
+    on_collapse_change: Option<EventHandler<bool>>,
+    #[props(into, default = "Navigation".to_string())] aria_label: String,
+) -> Element {
+    const HEADER_CLASS: Asset = asset!("/assets/header.css");
+    let mut menu_list = with_ids(menu_list, &id_prefix);
+    if let Some(held) = permissions.get() {
+        menu_list = permitted(menu_list, &held);
+    }
+    let mut state = use_context_provider(|| SideNavState {
+        collapsed: Signal::new(false),
+        expanded: Signal::new(vec![]),
+        storage_key: Signal::new(persist.then(|| format!("sam_ui.{id_prefix}"))),
+    });
+    let first_menus = use_hook(|| menu_list.clone());
+    use_effect(move || {
+        let Some((collapsed, mut expanded)) = state.storage_key.peek().as_deref().and_then(load)
+        else {
+            return;
+        };
+        // drops the sections removed since the state was saved
+        expanded.retain(|id| nav::path_to(&first_menus, id).is_some());
+        state.collapsed.set(collapsed);
+        state.expanded.set(expanded);
+    });
+    use_context_provider(ChildrenCache::new);
+    let collapsed = (state.collapsed)();
+    let mut toggle = move || {
+        state.set_collapsed(!collapsed);
+        if let Some(on_collapse_change) = on_collapse_change {
+            on_collapse_change.call(!collapsed);
+        }
+    };
+    rsx! {
+        document::Stylesheet { href: "{HEADER_CLASS}" }
+        nav {
+            class: "side_nav",
+            class: if collapsed { "rail" },
+            aria_label,
+            div {
+                class: "side_nav_toggle",
+                role: "button",
+                tabindex: 0,
+                aria_label: if collapsed { "Expand navigation" } else { "Collapse navigation" },
+                aria_expanded: "{!collapsed}",
+                onclick: move |_| toggle(),
+                onkeydown: move |e: Event<KeyboardData>| {
+                    if nav::is_activation(&e.key()) {
+                        e.prevent_default();
+                        toggle();
+                    }
+                },
+                if collapsed {
+                    {icon!(LdPanelLeftOpen, 20, "none", "currentColor")}
+                } else {
+                    {icon!(LdPanelLeftClose, 20, "none", "currentColor")}
+                }
+            }
+            div { class: "side_nav_list", role: "list",
+                for menu in menu_list {
+                    {menu.render_side()}
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub(crate) fn SideNavView(
+    label: MenuLabel,
+    id: String,
+    decor: MenuDecor,
+    disabled: MenuFlag,
+    check: Option<Check>,
+    route: Option<MenuRoute>,
+    sub_menu_list: Option<Vec<Menu>>,
+    loader: Option<ChildrenLoader>,
+    action: Option<Action>,
+    /// Whether the menu has a `Menu::panel`, which `SideNav` leaves out
+    has_panel: bool,
+) -> Element {
+    use_panel_warning(&id, has_panel, "`SideNav` has none");
+    let mut state = use_context::<SideNavState>();
+    let in_flyout = try_use_context::<InFlyout>().is_some();
+    let rail = (state.collapsed)() && !in_flyout;
+    let mut open = use_signal(|| false);
+    // the label, or the children of a section, next to the rail
+    let mut hovered = use_signal(|| false);
+    // async children load once the section expands or its flyout shows
+    let mut wanted = use_signal(|| false);
+    let id_clone_1 = id.clone();
+    use_effect(move || {
+        let expanded = state.is_expanded(&id_clone_1);
+        if *open.peek() != expanded {
+            open.set(expanded);
+        }
+        let shown = expanded || hovered();
+        if *wanted.peek() != shown {
+            wanted.set(shown);
+        }
+    });
+    let sub_menu_list = use_children(&id, sub_menu_list, loader, wanted);
+    let mut anchor: Signal<Option<web_sys::Element>> = use_signal(|| None);
+
+    let has_children = sub_menu_list.is_some();
+    let has_route = route.is_some();
+    let is_disabled = disabled.get();
+    let (is_active, is_current) = route_state(&route, &sub_menu_list);
+    let initial = label
+        .get()
+        .chars()
+        .next()
+        .map(|c| c.to_uppercase().to_string());
+
+    let mut on_activate = {
+        let id = id.clone();
+        let action = action.clone();
+        let check = check.clone();
+        let route = route.clone();
+        move || {
+            if disabled.get() {
+                return;
+            }
+            // a rail section shows its children in the flyout
+            if has_children && !rail {
+                state.toggle(&id);
+            }
+            activate(&action, &check);
+            if let Some(route) = &route {
+                route.push();
+            }
+        }
+    };
+
+    rsx! {
+        div {
+            class: "side_nav_item",
+            role: "listitem",
+            onpointerenter: move |_| hovered.set(rail),
+            onpointerleave: move |_| hovered.set(false),
+            onfocusin: move |_| hovered.set(rail),
+            onfocusout: move |_| hovered.set(false),
+            div {
+                class: "side_nav_row",
+                class: if rail { "rail" },
+                class: if is_disabled { "disabled" },
+                class: if is_active { "active" },
+                aria_current: is_current.then_some("page"),
+                id: "{id}",
+                role: if has_route { "link" } else { "button" },
+                tabindex: 0,
+                aria_label: rail.then(|| label.get()),
+                aria_pressed: check.as_ref().map(|c| c.is_checked().to_string()),
+                aria_disabled: is_disabled.then_some("true"),
+                aria_expanded: (has_children && !rail).then(|| open().to_string()),
+                aria_controls: (has_children && !rail).then(|| menu_list_id(&id)),
+                onmounted: move |elem: Event<MountedData>| {
+                    use dioxus::web::WebEventExt;
+                    anchor.set(Some(elem.as_web_event()));
+                },
+                onclick: {
+                    let mut on_activate = on_activate.clone();
+                    let route = route.clone();
+                    move |e: Event<MouseData>| {
+                        if !opens_elsewhere(&route, &e) {
+                            on_activate()
+                        }
+                    }
+                },
+                onkeydown: move |e: Event<KeyboardData>| {
+                    if nav::is_activation(&e.key()) {
+                        e.prevent_default();
+                        on_activate();
+                    }
+                },
+                if rail && decor.icon.is_none() {
+                    span { class: "side_nav_initial", aria_hidden: "true", {initial} }
+                }
+                {
+                    route_link(
+                        &route,
+                        is_disabled,
+                        rsx! {
+                            MenuContent {
+                                label: label.clone(),
+                                decor: decor.clone(),
+                                check: check.clone(),
+                                has_children,
+                                is_root: open(),
+                            }
+                        },
+                    )
+                }
+            }
+            if rail && hovered() {
+                SideNavFlyout {
+                    parent: id.clone(),
+                    anchor,
+                    label: label.clone(),
+                    sub_menu_list: sub_menu_list.clone(),
+                }
+            } else if has_children && open() && !rail {
+                div {
+                    class: "side_nav_section",
+                    id: menu_list_id(&id),
+                    role: "list",
+                    aria_labelledby: "{id}",
+                    for menu in sub_menu_list.unwrap_or_default() {
+                        {menu.render_side()}
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The label of a rail item, with the children of a section
+#[component]
+fn SideNavFlyout(
+    parent: String,
+    anchor: Signal<Option<web_sys::Element>>,
+    label: MenuLabel,
+    sub_menu_list: Option<Vec<Menu>>,
+) -> Element {
+    use_context_provider(|| InFlyout);
+    // shown as long as it is mounted
+    let open = use_signal(|| true);
+    let (mut flyout, position) = use_flyout(anchor, open, Placement::Beside);
+    let has_children = sub_menu_list.is_some();
+    rsx! {
+        div {
+            z_index: 11,
+            class: "side_nav_flyout",
+            id: has_children.then(|| menu_list_id(&parent)),
+            role: if has_children { "list" } else { "tooltip" },
+            style: flyout_style(position()),
+            onmounted: move |elem: Event<MountedData>| {
+                use dioxus::web::WebEventExt;
+                flyout.set(Some(elem.as_web_event()));
+            },
+            div { class: "side_nav_flyout_title", aria_hidden: "true", {label.get()} }
+            for menu in sub_menu_list.unwrap_or_default() {
+                {menu.render_side()}
+            }
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use web_sys::{window, Storage};
+
+fn local_storage() -> Option<Storage> {
+    window()?.local_storage().ok()?
+}
+
+/// Reads `key` from the local storage, `None` when missing or not available
+pub(crate) fn load(key: &str) -> Option<String> {
+    local_storage()?.get_item(key).ok()?
+}
+
+/// Writes `key` to the local storage, ignored when it is not available (e.g. private mode)
+pub(crate) fn save(key: &str, value: &str) {
+    if let Some(storage) = local_storage() {
+        _ = storage.set_item(key, value);
+    }
+}
@@ -1,4 +1,4 @@
-use super::Action;
+use super::{Action, MenuLabel};
 use dioxus::{logger::tracing::info, prelude::*};
 use std::rc::Rc;
 
@@ -10,17 +10,17 @@ pub struct SubMenuState {
 #[derive(Debug, Clone, PartialEq)]
 pub struct SubMenu {
     id: String,
-    label: &'static str,
+    label: MenuLabel,
     action: Option<Action>,
     sub_menu_list: Option<Vec<SubMenu>>,
 }
 
 impl SubMenu {
-    pub fn new(label: &'static str) -> SubMenu {
+    pub fn new(label: impl Into<MenuLabel>) -> SubMenu {
         let id = sam_util::gen_id!(5, "sub_menu_");
         SubMenu {
             id,
-            label,
+            label: label.into(),
             sub_menu_list: None,
             action: None,
         }
@@ -50,7 +50,7 @@ impl SubMenu {
 
 #[component]
 pub fn SubMenuView(
-    label: &'static str,
+    label: MenuLabel,
     id: String,
     sub_menu_list: Option<Vec<SubMenu>>,
     action: Option<Action>,
@@ -83,7 +83,7 @@ pub fn SubMenuView(
             onmouseleave: move |_| {
                 show_sub_menu.set(false);
             },
-            "{label}"
+            {label.get()}
             if sub_menu_list.is_some() && show_sub_menu() {
                 SubSubMenuWrapper { sub_menu_list }
             }
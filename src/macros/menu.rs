@@ -0,0 +1,185 @@
+/////////////////////////////////////////////////
+////// Menu
+/////////////////////////////////////////////////
+
+/// A macro building the `Vec<Menu>` of a `MenuBar` or `MenuList` from a nested list.
+///
+/// - `"Label"` or `(expression)` is a `Menu::new`, followed by any `Menu` builder calls.
+/// - `=> [ ... ]` gives it children.
+/// - `---` is a separator.
+/// - `# "Label"` is a heading, `# "Label" => [ ... ]` a group.
+///
+/// The menus of the outer list are roots, `to_root()` is added for you.
+/// A typo in a builder call points at the call itself.
+///
+/// # Examples
+///
+/// ```
+/// let menu_list = menu![
+///     "File" => [
+///         "Save".action(save).shortcut("Ctrl+S").icon(icon!(LdSave, 18)),
+///         "Export" => [
+///             "PDF".action(export_pdf),
+///             "PNG".action(export_png),
+///         ],
+///         ---,
+///         # "Sort by" => [
+///             "Name".radio("sort", Sort::Name, sort),
+///             "Date".radio("sort", Sort::Date, sort),
+///         ],
+///     ],
+///     (format!("Hello {}", user())).key("user"),
+/// ];
+/// ```
+///
+/// `"Export" => ["PDF".action(export_pdf)]` expands to :
+///
+/// ```
+/// Menu::new("Export").children(vec![Menu::new("PDF").action(export_pdf)])
+/// ```
+#[macro_export]
+macro_rules! menu {
+    // `root` adds `to_root()` to the menus of the outer list
+    (@root root $menu:expr) => {
+        $menu.to_root()
+    };
+    (@root nested $menu:expr) => {
+        $menu
+    };
+
+    // munches one menu of the list, then the rest after the comma
+    (@list $list:ident $root:tt) => {};
+    (@list $list:ident $root:tt ---
+        $(, $($rest:tt)*)?
+    ) => {
+        $list.push($crate::menu!(@root $root $crate::Menu::separator()));
+        $( $crate::menu!(@list $list $root $($rest)*); )?
+    };
+    (@list $list:ident $root:tt # $label:tt
+        $(. $method:ident ( $($args:tt)* ))*
+        => [ $($children:tt)* ]
+        $(, $($rest:tt)*)?
+    ) => {
+        $list.push($crate::menu!(@root $root
+            $crate::Menu::group($label, $crate::menu!(@nested $($children)*))
+                $(.$method($($args)*))*
+        ));
+        $( $crate::menu!(@list $list $root $($rest)*); )?
+    };
+    (@list $list:ident $root:tt # $label:tt
+        $(. $method:ident ( $($args:tt)* ))*
+        $(, $($rest:tt)*)?
+    ) => {
+        $list.push($crate::menu!(@root $root
+            $crate::Menu::heading($label) $(.$method($($args)*))*
+        ));
+        $( $crate::menu!(@list $list $root $($rest)*); )?
+    };
+    (@list $list:ident $root:tt $label:tt
+        $(. $method:ident ( $($args:tt)* ))*
+        $(=> [ $($children:tt)* ])?
+        $(, $($rest:tt)*)?
+    ) => {
+        $list.push($crate::menu!(@root $root
+            $crate::Menu::new($label)
+                $(.$method($($args)*))*
+                $(.children($crate::menu!(@nested $($children)*)))?
+        ));
+        $( $crate::menu!(@list $list $root $($rest)*); )?
+    };
+
+    (@nested $($items:tt)*) => {{
+        // pushing one by one, `vec![]` can't hold the munched items
+        #[allow(unused_mut, clippy::vec_init_then_push)]
+        let list = {
+            let mut list: ::std::vec::Vec<$crate::Menu> = ::std::vec::Vec::new();
+            $crate::menu!(@list list nested $($items)*);
+            list
+        };
+        list
+    }};
+
+    ($($items:tt)*) => {{
+        // pushing one by one, `vec![]` can't hold the munched items
+        #[allow(unused_mut, clippy::vec_init_then_push)]
+        let list = {
+            let mut list: ::std::vec::Vec<$crate::Menu> = ::std::vec::Vec::new();
+            $crate::menu!(@list list root $($items)*);
+            list
+        };
+        list
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Menu;
+
+    #[test]
+    fn menu_marks_outer_menus_as_roots() {
+        let menus = menu![
+            "File" => [
+                "Open",
+                "Recent" => ["a.txt"],
+            ],
+            "Help",
+        ];
+        let expected = vec![
+            Menu::new("File")
+                .children(vec![
+                    Menu::new("Open"),
+                    Menu::new("Recent").children(vec![Menu::new("a.txt")]),
+                ])
+                .to_root(),
+            Menu::new("Help").to_root(),
+        ];
+        assert_eq!(menus, expected);
+        assert!(!menus[0].sub_menus().unwrap()[1].is_root());
+    }
+
+    #[test]
+    fn menu_keeps_builder_calls_and_expression_labels() {
+        let name = "Sam";
+        let menus = menu![
+            "Save".shortcut("Ctrl+S").disabled(true) => ["Copy"],
+            (format!("Hello {name}")).key("user"),
+        ];
+        let expected = vec![
+            Menu::new("Save")
+                .shortcut("Ctrl+S")
+                .disabled(true)
+                .children(vec![Menu::new("Copy")])
+                .to_root(),
+            Menu::new("Hello Sam").key("user").to_root(),
+        ];
+        assert_eq!(menus, expected);
+    }
+
+    #[test]
+    fn menu_builds_separators_headings_and_groups() {
+        let menus = menu![
+            "View" => [
+                # "Zoom",
+                "In",
+                ---,
+                # "Sort by".key("sort") => ["Name", "Date"],
+            ],
+        ];
+        let expected = vec![Menu::new("View")
+            .children(vec![
+                Menu::heading("Zoom"),
+                Menu::new("In"),
+                Menu::separator(),
+                Menu::group("Sort by", vec![Menu::new("Name"), Menu::new("Date")]).key("sort"),
+            ])
+            .to_root()];
+        assert_eq!(menus, expected);
+    }
+
+    #[test]
+    fn menu_accepts_an_empty_list_and_trailing_commas() {
+        let menus: Vec<Menu> = menu![];
+        assert!(menus.is_empty());
+        assert_eq!(menu!["Help",], vec![Menu::new("Help").to_root()]);
+    }
+}